@@ -1,16 +1,104 @@
-use std::env;
+use std::{env, path::PathBuf, time::Duration};
 
 use anyhow::Context;
 
+use crate::{
+    infrastructure::{
+        CacheMode, DiskCache, NameSourceKinds, NameSourceOptions, NamegenClientOptions,
+        NamesCacheOptions,
+    },
+    model::Rarity,
+};
+
+/// Seconds
+const DEFAULT_NAMES_REFRESH_INTERVAL: u64 = 10 * 60;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Config {
     pub base_path: String,
+    /// `None` disables the disk cache
+    pub cache_dir: Option<PathBuf>,
+    pub cache_ttl: Duration,
+    pub name_rarity: Rarity,
+    pub name_source: NameSourceKinds,
+    /// `None` uses the proxy in the environment
+    pub namegen_proxy: Option<String>,
+    pub namegen_retries: u32,
+    pub namegen_timeout: Duration,
+    pub namegen_user_agent: String,
+    /// A CSV or JSON file of names
+    pub names_file: Option<PathBuf>,
+    /// The maximum number of names kept per sex
+    pub names_pool_size: usize,
+    /// `None` disables pre-fetching names in the background
+    pub names_refresh_interval: Option<Duration>,
     pub port: u16,
 }
 
 impl Config {
     pub fn from_env() -> anyhow::Result<Self> {
         let base_path = get_var_opt("BASE_PATH")?.unwrap_or_default();
+        // an empty CACHE_DIR disables the disk cache
+        let cache_dir = match get_var_opt("CACHE_DIR")? {
+            Some(s) if s.is_empty() => None,
+            Some(s) => Some(PathBuf::from(s)),
+            None => DiskCache::default_dir(),
+        };
+        let cache_ttl = get_var_opt("CACHE_TTL")?
+            .map(|s| {
+                s.parse::<u64>()
+                    .map(Duration::from_secs)
+                    .context("CACHE_TTL must be seconds")
+            })
+            .transpose()?
+            .unwrap_or(NamesCacheOptions::DEFAULT_TTL);
+        let name_rarity = get_var_opt("NAME_RARITY")?
+            .map(|s| s.parse::<Rarity>())
+            .transpose()?
+            .unwrap_or_default();
+        let name_source = get_var_opt("NAME_SOURCE")?
+            .map(|s| s.parse::<NameSourceKinds>())
+            .transpose()?
+            .unwrap_or_default();
+        let namegen_proxy = get_var_opt("NAMEGEN_PROXY")?.filter(|s| !s.is_empty());
+        let namegen_retries = get_var_opt("NAMEGEN_RETRIES")?
+            .map(|s| s.parse::<u32>().context("NAMEGEN_RETRIES must be a number"))
+            .transpose()?
+            .unwrap_or(NamegenClientOptions::DEFAULT_RETRIES);
+        let namegen_timeout = get_var_opt("NAMEGEN_TIMEOUT")?
+            .map(|s| {
+                s.parse::<u64>()
+                    .ok()
+                    .filter(|secs| *secs > 0)
+                    .map(Duration::from_secs)
+                    .context("NAMEGEN_TIMEOUT must be positive seconds")
+            })
+            .transpose()?
+            .unwrap_or(NamegenClientOptions::DEFAULT_TIMEOUT);
+        let namegen_user_agent = get_var_opt("NAMEGEN_USER_AGENT")?
+            .unwrap_or_else(|| NamegenClientOptions::DEFAULT_USER_AGENT.to_owned());
+        let names_file = get_var_opt("NAMES_FILE")?
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from);
+        let names_pool_size = get_var_opt("NAMES_POOL_SIZE")?
+            .map(|s| {
+                s.parse::<usize>()
+                    .ok()
+                    .filter(|size| *size > 0)
+                    .context("NAMES_POOL_SIZE must be a positive number")
+            })
+            .transpose()?
+            .unwrap_or(NamesCacheOptions::DEFAULT_POOL_SIZE);
+        // NAMES_REFRESH_INTERVAL=0 disables pre-fetching
+        let names_refresh_interval = get_var_opt("NAMES_REFRESH_INTERVAL")?
+            .map(|s| {
+                s.parse::<u64>()
+                    .context("NAMES_REFRESH_INTERVAL must be seconds")
+            })
+            .transpose()?
+            .unwrap_or(DEFAULT_NAMES_REFRESH_INTERVAL);
+        let names_refresh_interval =
+            (names_refresh_interval > 0).then(|| Duration::from_secs(names_refresh_interval));
         let port = get_var_opt("PORT")
             .map(|s| s.unwrap_or("3000".to_owned()))
             .and_then(|s| {
@@ -18,7 +106,50 @@ impl Config {
                     .parse::<u16>()
                     .context("PORT range is (0..=65535)")
             })?;
-        Ok(Self { base_path, port })
+        Ok(Self {
+            base_path,
+            cache_dir,
+            cache_ttl,
+            name_rarity,
+            name_source,
+            namegen_proxy,
+            namegen_retries,
+            namegen_timeout,
+            namegen_user_agent,
+            names_file,
+            names_pool_size,
+            names_refresh_interval,
+            port,
+        })
+    }
+
+    pub fn name_source_options(&self) -> NameSourceOptions {
+        NameSourceOptions {
+            kinds: self.name_source.clone(),
+            names_file: self.names_file.clone(),
+            rarity: self.name_rarity,
+            client: self.namegen_client_options(),
+            cache: self.names_cache_options(),
+        }
+    }
+
+    fn namegen_client_options(&self) -> NamegenClientOptions {
+        NamegenClientOptions {
+            backoff: NamegenClientOptions::DEFAULT_BACKOFF,
+            proxy: self.namegen_proxy.clone(),
+            retries: self.namegen_retries,
+            timeout: self.namegen_timeout,
+            user_agent: self.namegen_user_agent.clone(),
+        }
+    }
+
+    fn names_cache_options(&self) -> NamesCacheOptions {
+        NamesCacheOptions {
+            disk_cache: self.cache_dir.clone().map(DiskCache::new),
+            mode: CacheMode::Normal,
+            pool_size: self.names_pool_size,
+            ttl: self.cache_ttl,
+        }
     }
 }
 
@@ -63,6 +194,17 @@ mod tests {
         fn default() -> Self {
             let mut env_vars = HashMap::new();
             env_vars.insert("BASE_PATH", Some(OsStr::new("/lab/genpi")));
+            env_vars.insert("CACHE_DIR", Some(OsStr::new("/var/cache/genpi")));
+            env_vars.insert("CACHE_TTL", Some(OsStr::new("3600")));
+            env_vars.insert("NAME_RARITY", Some(OsStr::new("any")));
+            env_vars.insert("NAME_SOURCE", Some(OsStr::new("namegen")));
+            env_vars.insert("NAMEGEN_PROXY", Some(OsStr::new("")));
+            env_vars.insert("NAMEGEN_RETRIES", Some(OsStr::new("3")));
+            env_vars.insert("NAMEGEN_TIMEOUT", Some(OsStr::new("10")));
+            env_vars.insert("NAMEGEN_USER_AGENT", Some(OsStr::new("genpi")));
+            env_vars.insert("NAMES_FILE", Some(OsStr::new("")));
+            env_vars.insert("NAMES_POOL_SIZE", Some(OsStr::new("1000")));
+            env_vars.insert("NAMES_REFRESH_INTERVAL", Some(OsStr::new("600")));
             env_vars.insert("PORT", Some(OsStr::new("3000")));
             Self(env_vars)
         }
@@ -73,6 +215,17 @@ mod tests {
         temp_env::with_vars(
             EnvVars::default()
                 .unset("BASE_PATH")
+                .unset("CACHE_DIR")
+                .unset("CACHE_TTL")
+                .unset("NAME_RARITY")
+                .unset("NAME_SOURCE")
+                .unset("NAMEGEN_PROXY")
+                .unset("NAMEGEN_RETRIES")
+                .unset("NAMEGEN_TIMEOUT")
+                .unset("NAMEGEN_USER_AGENT")
+                .unset("NAMES_FILE")
+                .unset("NAMES_POOL_SIZE")
+                .unset("NAMES_REFRESH_INTERVAL")
                 .unset("PORT")
                 .into_kvs(),
             || {
@@ -81,6 +234,17 @@ mod tests {
                     config,
                     Config {
                         base_path: "".to_owned(),
+                        cache_dir: DiskCache::default_dir(),
+                        cache_ttl: Duration::from_secs(3600),
+                        name_rarity: Rarity::Any,
+                        name_source: NameSourceKinds::default(),
+                        namegen_proxy: None,
+                        namegen_retries: 3,
+                        namegen_timeout: Duration::from_secs(10),
+                        namegen_user_agent: NamegenClientOptions::DEFAULT_USER_AGENT.to_owned(),
+                        names_file: None,
+                        names_pool_size: 1000,
+                        names_refresh_interval: Some(Duration::from_secs(600)),
                         port: 3000
                     }
                 );
@@ -94,6 +258,17 @@ mod tests {
         temp_env::with_vars(
             EnvVars::default()
                 .set("BASE_PATH", b"/lab/genpi")
+                .set("CACHE_DIR", b"/tmp/genpi")
+                .set("CACHE_TTL", b"60")
+                .set("NAME_RARITY", b"rare")
+                .set("NAME_SOURCE", b"dictionary")
+                .set("NAMEGEN_PROXY", b"http://proxy.example.com:8080")
+                .set("NAMEGEN_RETRIES", b"0")
+                .set("NAMEGEN_TIMEOUT", b"30")
+                .set("NAMEGEN_USER_AGENT", b"genpi-test")
+                .set("NAMES_FILE", b"/etc/genpi/names.csv")
+                .set("NAMES_POOL_SIZE", b"5000")
+                .set("NAMES_REFRESH_INTERVAL", b"0")
                 .set("PORT", b"3000")
                 .into_kvs(),
             || {
@@ -102,6 +277,17 @@ mod tests {
                     config,
                     Config {
                         base_path: "/lab/genpi".to_owned(),
+                        cache_dir: Some(PathBuf::from("/tmp/genpi")),
+                        cache_ttl: Duration::from_secs(60),
+                        name_rarity: Rarity::Rare,
+                        name_source: "dictionary".parse()?,
+                        namegen_proxy: Some("http://proxy.example.com:8080".to_owned()),
+                        namegen_retries: 0,
+                        namegen_timeout: Duration::from_secs(30),
+                        namegen_user_agent: "genpi-test".to_owned(),
+                        names_file: Some(PathBuf::from("/etc/genpi/names.csv")),
+                        names_pool_size: 5000,
+                        names_refresh_interval: None,
                         port: 3000
                     }
                 );
@@ -126,6 +312,86 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_cache_dir_is_empty() -> anyhow::Result<()> {
+        temp_env::with_vars(EnvVars::default().set("CACHE_DIR", b"").into_kvs(), || {
+            assert_eq!(Config::from_env()?.cache_dir, None);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_cache_ttl_is_not_number() -> anyhow::Result<()> {
+        temp_env::with_vars(
+            EnvVars::default().set("CACHE_TTL", b"1h").into_kvs(),
+            || {
+                assert_eq!(
+                    Config::from_env().unwrap_err().to_string(),
+                    "CACHE_TTL must be seconds"
+                );
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    fn test_name_source_is_invalid() -> anyhow::Result<()> {
+        temp_env::with_vars(
+            EnvVars::default().set("NAME_SOURCE", b"foo").into_kvs(),
+            || {
+                assert_eq!(
+                    Config::from_env().unwrap_err().to_string(),
+                    "foo is not a valid name source (namegen|cache|dictionary|file)"
+                );
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    fn test_namegen_timeout_is_zero() -> anyhow::Result<()> {
+        temp_env::with_vars(
+            EnvVars::default().set("NAMEGEN_TIMEOUT", b"0").into_kvs(),
+            || {
+                assert_eq!(
+                    Config::from_env().unwrap_err().to_string(),
+                    "NAMEGEN_TIMEOUT must be positive seconds"
+                );
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    fn test_names_pool_size_is_zero() -> anyhow::Result<()> {
+        temp_env::with_vars(
+            EnvVars::default().set("NAMES_POOL_SIZE", b"0").into_kvs(),
+            || {
+                assert_eq!(
+                    Config::from_env().unwrap_err().to_string(),
+                    "NAMES_POOL_SIZE must be a positive number"
+                );
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    fn test_names_refresh_interval_is_not_number() -> anyhow::Result<()> {
+        temp_env::with_vars(
+            EnvVars::default()
+                .set("NAMES_REFRESH_INTERVAL", b"10m")
+                .into_kvs(),
+            || {
+                assert_eq!(
+                    Config::from_env().unwrap_err().to_string(),
+                    "NAMES_REFRESH_INTERVAL must be seconds"
+                );
+                Ok(())
+            },
+        )
+    }
+
     #[test]
     fn test_port_is_not_unicode() -> anyhow::Result<()> {
         temp_env::with_vars(
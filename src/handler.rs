@@ -1 +1,2 @@
 pub mod generate_pi;
+pub mod wareki;
@@ -1,28 +1,74 @@
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     routing::get,
-    Json, Router,
+    Router,
 };
 
 use crate::{
-    model::{GenNameError, GenPiError, KanaForm, PI},
-    use_case::{GeneratePiUseCase, HasGeneratePiUseCase},
+    model::{
+        AgeDistribution, Charset, DateFormat, DateOfBirth, DateOfBirthConstraints, EmailDomains,
+        FemaleRatio, GenNameError, GenPiError, KanaForm, NameFilter, Normalization,
+        PhoneNumberFormat, PhoneNumberType, Romanization, Sex, WarekiFormat, PI,
+    },
+    output::{
+        self, ColumnNames, Encoding, Fields, OutputFormat, OutputOptions, SqlDialect, SqlOptions,
+    },
+    use_case::{GeneratePiOptions, GeneratePiUseCase, HasGeneratePiUseCase},
 };
 
+/// The upper bound of `count` to keep the response size reasonable
+const MAX_COUNT: usize = 1_000;
+
 #[derive(Debug, serde::Deserialize)]
 pub struct GetRootQuery {
+    address: Option<bool>,
+    age: Option<bool>,
+    age_distribution: Option<AgeDistribution>,
+    as_of: Option<DateOfBirth>,
+    born_after: Option<DateOfBirth>,
+    born_before: Option<DateOfBirth>,
+    charset: Option<Charset>,
+    count: Option<usize>,
+    date_format: Option<DateFormat>,
+    day_of_week: Option<bool>,
+    edge_cases: Option<bool>,
+    email: Option<bool>,
+    email_domains: Option<EmailDomains>,
+    encoding: Option<Encoding>,
+    female_ratio: Option<FemaleRatio>,
+    fields: Option<Fields>,
+    format: Option<OutputFormat>,
     halfwidth: Option<bool>,
     katakana: Option<bool>,
+    max_age: Option<u16>,
+    min_age: Option<u16>,
+    name_filter: Option<NameFilter>,
+    normalization: Option<Normalization>,
+    phone: Option<PhoneNumberType>,
+    phone_format: Option<PhoneNumberFormat>,
+    romaji: Option<Romanization>,
+    seed: Option<u64>,
+    sex: Option<Sex>,
+    source: Option<bool>,
+    sql_columns: Option<ColumnNames>,
+    sql_dialect: Option<SqlDialect>,
+    sql_rows_per_statement: Option<usize>,
+    star_sign: Option<bool>,
+    table: Option<String>,
+    wareki: Option<WarekiFormat>,
+    zodiac: Option<bool>,
 }
 
 #[tracing::instrument(skip_all)]
-async fn handler<T: std::fmt::Debug>(
+async fn handler<T>(
     State(state): State<T>,
+    headers: HeaderMap,
     Query(q): Query<GetRootQuery>,
-) -> Result<Json<PI>, StatusCode>
+) -> Result<Response, StatusCode>
 where
-    T: Clone + HasGeneratePiUseCase + Send + Sync,
+    T: Clone + std::fmt::Debug + HasGeneratePiUseCase + Send + Sync,
 {
     let pi_generator = state.generate_pi_use_case();
 
@@ -35,16 +81,120 @@ where
         (true, true) => KanaForm::HalfwidthKana,
     };
 
-    let pi = pi_generator
-        .generate_pi(kana_form)
+    // the `format` parameter takes precedence over the `Accept` header
+    let format = match (q.format, headers.get(header::ACCEPT)) {
+        (Some(format), _) => format,
+        (None, None) => OutputFormat::Json,
+        (None, Some(accept)) => accept
+            .to_str()
+            .ok()
+            .and_then(OutputFormat::from_accept)
+            .ok_or(StatusCode::NOT_ACCEPTABLE)?,
+    };
+
+    let encoding = q.encoding.unwrap_or_default();
+    if !format.allows(encoding) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let sql_options = SqlOptions::default();
+    let output_options = OutputOptions {
+        date_format: q.date_format,
+        fields: q.fields,
+        format,
+        sql: SqlOptions {
+            column_names: q.sql_columns.unwrap_or(sql_options.column_names),
+            dialect: q.sql_dialect.unwrap_or(sql_options.dialect),
+            rows_per_statement: match q.sql_rows_per_statement {
+                Some(0) => return Err(StatusCode::BAD_REQUEST),
+                Some(n) => n,
+                None => sql_options.rows_per_statement,
+            },
+            table: q.table.unwrap_or(sql_options.table),
+        },
+    };
+
+    if q.sex.is_some() && q.female_ratio.is_some() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    if q.count.is_some_and(|count| count == 0 || count > MAX_COUNT) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut options = GeneratePiOptions {
+        address: q.address.unwrap_or_default(),
+        age: q.age.unwrap_or_default(),
+        age_distribution: q.age_distribution.unwrap_or_default(),
+        as_of: q.as_of,
+        // names must be encodable in the output encoding
+        charset: q.charset.or(encoding.charset()),
+        date_of_birth: DateOfBirthConstraints {
+            min_age: q.min_age,
+            max_age: q.max_age,
+            born_after: q.born_after,
+            born_before: q.born_before,
+        },
+        day_of_week: q.day_of_week.unwrap_or_default(),
+        edge_cases: q.edge_cases.unwrap_or_default(),
+        email: q.email.unwrap_or_default(),
+        email_domains: q.email_domains.unwrap_or_default(),
+        female_ratio: q.female_ratio,
+        kana_form,
+        name_filter: q.name_filter.unwrap_or_default(),
+        normalization: q.normalization,
+        phone_number: q.phone,
+        phone_number_format: q.phone_format.unwrap_or_default(),
+        romanization: q.romaji,
+        seed: q.seed,
+        sex: q.sex,
+        source: q.source.unwrap_or_default(),
+        star_sign: q.star_sign.unwrap_or_default(),
+        wareki: q.wareki,
+        zodiac: q.zodiac.unwrap_or_default(),
+    };
+    // selecting a field turns it on
+    if let Some(fields) = &output_options.fields {
+        fields
+            .check(PI::FIELDS)
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+        options.enable_fields(fields.names());
+    }
+    let mut pis = pi_generator
+        .generate_pis(q.count.unwrap_or(1), options)
         .await
         .map_err(|e| match e {
             GenPiError::GenNameError(e) => match e {
-                GenNameError::RequestFailure => StatusCode::INTERNAL_SERVER_ERROR,
-                GenNameError::Conflict => StatusCode::CONFLICT,
+                GenNameError::Network(_) | GenNameError::Status(_) | GenNameError::Parse(_) => {
+                    StatusCode::BAD_GATEWAY
+                }
+                GenNameError::Timeout => StatusCode::GATEWAY_TIMEOUT,
+                GenNameError::Offline => StatusCode::SERVICE_UNAVAILABLE,
+                GenNameError::NoNames
+                | GenNameError::NoMatch
+                | GenNameError::UnsupportedRarity(_) => StatusCode::UNPROCESSABLE_ENTITY,
             },
+            GenPiError::DateOfBirthRange(_) => StatusCode::BAD_REQUEST,
+            GenPiError::Romanization(_) | GenPiError::Email(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            // the date of birth range reaches before Meiji
+            GenPiError::Wareki(_) => StatusCode::BAD_REQUEST,
+            GenPiError::Charset(_) => StatusCode::BAD_REQUEST,
         })?;
-    Ok(Json(pi))
+    let body = match q.count {
+        // without `count`, respond with a single object for compatibility
+        None => output::render_one(&output_options, &pis.pop().expect("pis.len() == 1")),
+        Some(_) => output::render(&output_options, &pis),
+    }
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    // addresses may not be encodable
+    let body = encoding
+        .encode(&body)
+        .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+    Ok((
+        [(header::CONTENT_TYPE, format.content_type_in(encoding))],
+        body,
+    )
+        .into_response())
 }
 
 pub fn route<T>() -> Router<T>
@@ -57,10 +207,11 @@ where
 #[cfg(test)]
 mod tests {
     use axum::{body::Body, http::Request};
+    use rand::RngCore;
     use tower::ServiceExt;
 
     use crate::{
-        model::{Name, Sex},
+        model::{HasNameGenerator, Name, NameGenerator, Rarity, PI},
         use_case::GeneratePiUseCase,
     };
 
@@ -71,23 +222,42 @@ mod tests {
 
     #[async_trait::async_trait]
     impl GeneratePiUseCase for MockPiGenerator {
-        async fn generate_pi(&self, _kana_form: KanaForm) -> Result<PI, GenPiError> {
-            let sex = Sex::Male;
-            let name = Name {
-                first_name: "山田".to_string(),
-                first_name_kana: "やまだ".to_string(),
-                last_name: "太郎".to_string(),
-                last_name_kana: "たろう".to_string(),
-            };
-            let date_of_birth = "2020-01-02".parse().expect("valid date");
-            Ok(PI {
-                date_of_birth,
-                first_name: name.first_name,
-                first_name_kana: name.first_name_kana,
-                last_name: name.last_name,
-                last_name_kana: name.last_name_kana,
-                sex,
-            })
+        async fn generate_pis(
+            &self,
+            count: usize,
+            _options: GeneratePiOptions,
+        ) -> Result<Vec<PI>, GenPiError> {
+            Ok((0..count).map(|_| mock_pi()).collect())
+        }
+    }
+
+    fn mock_pi() -> PI {
+        let sex = Sex::Male;
+        let name = Name {
+            first_name: "山田".to_string(),
+            first_name_kana: "やまだ".to_string(),
+            last_name: "太郎".to_string(),
+            last_name_kana: "たろう".to_string(),
+        };
+        let date_of_birth = "2020-01-02".parse().expect("valid date");
+        PI {
+            age: None,
+            date_of_birth,
+            date_of_birth_wareki: None,
+            day_of_week: None,
+            email: None,
+            first_name: name.first_name,
+            first_name_kana: name.first_name_kana,
+            first_name_romaji: None,
+            last_name: name.last_name,
+            last_name_kana: name.last_name_kana,
+            last_name_romaji: None,
+            phone_number: None,
+            sex,
+            source: None,
+            star_sign: None,
+            zodiac: None,
+            address: None,
         }
     }
 
@@ -103,6 +273,40 @@ mod tests {
         }
     }
 
+    /// Runs the real use case with a fixed name
+    #[derive(Clone, Debug)]
+    struct MockNameApp;
+
+    #[async_trait::async_trait]
+    impl NameGenerator for MockNameApp {
+        async fn generate(
+            &self,
+            _sex: Sex,
+            _rng: &mut (dyn RngCore + Send),
+        ) -> Result<Name, GenNameError> {
+            Ok(Name {
+                first_name: "太郎".to_string(),
+                first_name_kana: "たろう".to_string(),
+                last_name: "山田".to_string(),
+                last_name_kana: "やまだ".to_string(),
+            })
+        }
+    }
+
+    impl HasNameGenerator for MockNameApp {
+        type NameGenerator = Self;
+        fn name_generator(&self) -> &Self::NameGenerator {
+            self
+        }
+    }
+
+    impl HasGeneratePiUseCase for MockNameApp {
+        type GeneratePiUseCase = Self;
+        fn generate_pi_use_case(&self) -> &Self::GeneratePiUseCase {
+            self
+        }
+    }
+
     #[tokio::test]
     async fn test() -> anyhow::Result<()> {
         let app = route().with_state(MockApp {
@@ -123,4 +327,267 @@ mod tests {
         );
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_invalid_query() -> anyhow::Result<()> {
+        let app = route().with_state(MockApp {
+            pi_generator: MockPiGenerator,
+        });
+
+        for uri in [
+            "/?seed=abc",
+            "/?sex=other",
+            "/?female_ratio=2",
+            "/?sex=female&female_ratio=0.5",
+        ] {
+            let response = app
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty())?)
+                .await?;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_invalid_date_of_birth_range() -> anyhow::Result<()> {
+        let app = route().with_state(MockNameApp);
+        for (uri, status) in [
+            ("/?min_age=20&max_age=30", StatusCode::OK),
+            ("/?min_age=30&max_age=20", StatusCode::BAD_REQUEST),
+            (
+                "/?born_after=2000-01-01&born_before=1990-01-01",
+                StatusCode::BAD_REQUEST,
+            ),
+            ("/?born_after=2000/01/01", StatusCode::BAD_REQUEST),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty())?)
+                .await?;
+            assert_eq!(response.status(), status, "{uri}");
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_gen_name_error() -> anyhow::Result<()> {
+        /// Fails with the error
+        #[derive(Clone, Debug)]
+        struct MockPiGenerator(GenNameError);
+
+        #[async_trait::async_trait]
+        impl GeneratePiUseCase for MockPiGenerator {
+            async fn generate_pis(
+                &self,
+                _count: usize,
+                _options: GeneratePiOptions,
+            ) -> Result<Vec<PI>, GenPiError> {
+                Err(GenPiError::GenNameError(self.0.clone()))
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        struct MockApp(MockPiGenerator);
+
+        impl HasGeneratePiUseCase for MockApp {
+            type GeneratePiUseCase = MockPiGenerator;
+            fn generate_pi_use_case(&self) -> &Self::GeneratePiUseCase {
+                &self.0
+            }
+        }
+
+        for (error, status) in [
+            (
+                GenNameError::Network("connection refused".to_owned()),
+                StatusCode::BAD_GATEWAY,
+            ),
+            (GenNameError::Timeout, StatusCode::GATEWAY_TIMEOUT),
+            (GenNameError::Status(503), StatusCode::BAD_GATEWAY),
+            (
+                GenNameError::Parse("td.name not found".to_owned()),
+                StatusCode::BAD_GATEWAY,
+            ),
+            (GenNameError::Offline, StatusCode::SERVICE_UNAVAILABLE),
+            (GenNameError::NoNames, StatusCode::UNPROCESSABLE_ENTITY),
+            (GenNameError::NoMatch, StatusCode::UNPROCESSABLE_ENTITY),
+            (
+                GenNameError::UnsupportedRarity(Rarity::Rare),
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+        ] {
+            let app = route().with_state(MockApp(MockPiGenerator(error.clone())));
+            let response = app
+                .oneshot(Request::builder().uri("/").body(Body::empty())?)
+                .await?;
+            assert_eq!(response.status(), status, "{error:?}");
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_count() -> anyhow::Result<()> {
+        let app = route().with_state(MockApp {
+            pi_generator: MockPiGenerator,
+        });
+
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri("/?count=2").body(Body::empty())?)
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        let body = String::from_utf8(bytes[..].to_vec())?;
+        let pi = r#"{"date_of_birth":"2020-01-02","first_name":"山田","first_name_kana":"やまだ","last_name":"太郎","last_name_kana":"たろう","sex":"male"}"#;
+        assert_eq!(body, format!("[{pi},{pi}]"));
+
+        for uri in ["/?count=0", "/?count=1001"] {
+            let response = app
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty())?)
+                .await?;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_format() -> anyhow::Result<()> {
+        let app = route().with_state(MockApp {
+            pi_generator: MockPiGenerator,
+        });
+        let csv = "date_of_birth,first_name,first_name_kana,last_name,last_name_kana,sex\r\n2020-01-02,山田,やまだ,太郎,たろう,male\r\n";
+
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri("/?format=csv").body(Body::empty())?)
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/csv; charset=utf-8; header=present"
+        );
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        assert_eq!(String::from_utf8(bytes[..].to_vec())?, csv);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/")
+                    .header(header::ACCEPT, "text/csv")
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        assert_eq!(String::from_utf8(bytes[..].to_vec())?, csv);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/")
+                    .header(header::ACCEPT, "text/html")
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri("/?format=xml").body(Body::empty())?)
+            .await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/?format=csv&encoding=sjis")
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/csv; charset=Shift_JIS; header=present"
+        );
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        assert_eq!(encoding_rs::SHIFT_JIS.decode(&bytes).0, csv);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/?format=json&encoding=sjis")
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/?format=sql&table=users&sql_dialect=mysql&sql_columns=first_name:given_name")
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        assert_eq!(
+            String::from_utf8(bytes[..].to_vec())?,
+            "INSERT INTO `users` (`date_of_birth`, `given_name`, `first_name_kana`, `last_name`, `last_name_kana`, `sex`) VALUES\n  ('2020-01-02', '山田', 'やまだ', '太郎', 'たろう', 'male');\n"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fields() -> anyhow::Result<()> {
+        let app = route().with_state(MockApp {
+            pi_generator: MockPiGenerator,
+        });
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/?fields=last_name:family_name,first_name:given_name")
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        assert_eq!(
+            String::from_utf8(bytes[..].to_vec())?,
+            r#"{"family_name":"太郎","given_name":"山田"}"#
+        );
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/?fields=first_name,nickname")
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // selecting email turns it on in the real use case
+        let response = route()
+            .with_state(MockNameApp)
+            .oneshot(
+                Request::builder()
+                    .uri("/?fields=first_name,email&seed=1")
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        let pi = serde_json::from_slice::<serde_json::Value>(&bytes)?;
+        assert_eq!(pi["first_name"], "太郎");
+        assert!(pi["email"]
+            .as_str()
+            .is_some_and(|email| email.contains("yamada")));
+        Ok(())
+    }
 }
@@ -0,0 +1,97 @@
+use axum::{extract::Query, http::StatusCode, routing::get, Json, Router};
+use time::Date;
+
+use crate::model::{DateOfBirth, Wareki, WarekiFormat};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct GetWarekiQuery {
+    /// `YYYY-MM-DD` or wareki (e.g. `令和元年5月1日`, `R1.5.1`)
+    date: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct WarekiResponse {
+    abbreviated: String,
+    date: DateOfBirth,
+    era: &'static str,
+    era_abbreviation: char,
+    era_year: u16,
+    kanji: String,
+}
+
+#[tracing::instrument]
+async fn handler(Query(q): Query<GetWarekiQuery>) -> Result<Json<WarekiResponse>, StatusCode> {
+    let (date, wareki) = match q.date.parse::<DateOfBirth>() {
+        Ok(date) => (
+            date,
+            Wareki::try_from(Date::from(date)).map_err(|_| StatusCode::BAD_REQUEST)?,
+        ),
+        Err(_) => {
+            let wareki = q
+                .date
+                .parse::<Wareki>()
+                .map_err(|_| StatusCode::BAD_REQUEST)?;
+            let date = Date::try_from(wareki).map_err(|_| StatusCode::BAD_REQUEST)?;
+            (DateOfBirth::from(date), wareki)
+        }
+    };
+    Ok(Json(WarekiResponse {
+        abbreviated: wareki.format(WarekiFormat::Abbreviated),
+        date,
+        era: wareki.era.name(),
+        era_abbreviation: wareki.era.abbreviation(),
+        era_year: wareki.year,
+        kanji: wareki.format(WarekiFormat::Kanji),
+    }))
+}
+
+pub fn route<T>() -> Router<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    Router::new().route("/wareki", get(handler))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http::Request};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test() -> anyhow::Result<()> {
+        let app = route::<()>().with_state(());
+        let expected = r#"{"abbreviated":"R1.5.1","date":"2019-05-01","era":"令和","era_abbreviation":"R","era_year":1,"kanji":"令和元年5月1日"}"#;
+        for date in [
+            "2019-05-01",
+            "%E4%BB%A4%E5%92%8C%E5%85%83%E5%B9%B45%E6%9C%881%E6%97%A5",
+            "R01.05.01",
+        ] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/wareki?date={date}"))
+                        .body(Body::empty())?,
+                )
+                .await?;
+            assert_eq!(response.status(), StatusCode::OK);
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            assert_eq!(String::from_utf8(bytes[..].to_vec())?, expected, "{date}");
+        }
+
+        for date in ["1868-10-22", "H31.5.1", "2019-13-01", ""] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/wareki?date={date}"))
+                        .body(Body::empty())?,
+                )
+                .await?;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{date}");
+        }
+        Ok(())
+    }
+}
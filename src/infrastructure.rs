@@ -1,3 +1,13 @@
+mod dictionary;
+mod disk_cache;
+mod name_source;
 mod namegen;
+mod namegen_client;
+mod names_file;
 
+pub use self::dictionary::*;
+pub use self::disk_cache::*;
+pub use self::name_source::*;
 pub use self::namegen::*;
+pub use self::namegen_client::*;
+pub use self::names_file::*;
@@ -0,0 +1,234 @@
+use std::sync::OnceLock;
+
+use anyhow::Context;
+use rand::{seq::SliceRandom, RngCore};
+
+use crate::model::{GenNameError, Name, NameFilter, NameGenerator, Rarity, Sex};
+
+const LAST_NAMES: &str = include_str!("dictionary/last_names.tsv");
+const FIRST_NAMES: &str = include_str!("dictionary/first_names.tsv");
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Entry {
+    name: String,
+    kana: String,
+}
+
+#[derive(Debug)]
+struct Dictionary {
+    last_names: Vec<Entry>,
+    female_first_names: Vec<Entry>,
+    male_first_names: Vec<Entry>,
+}
+
+impl Dictionary {
+    fn get() -> &'static Self {
+        static DICTIONARY: OnceLock<Dictionary> = OnceLock::new();
+        DICTIONARY.get_or_init(|| Self::parse().expect("embedded dictionary is valid"))
+    }
+
+    fn parse() -> anyhow::Result<Self> {
+        let mut last_names = vec![];
+        for line in LAST_NAMES.lines().filter(|line| !line.is_empty()) {
+            let (name, kana) = line
+                .split_once('\t')
+                .with_context(|| format!("invalid last name entry: {line}"))?;
+            last_names.push(Entry {
+                name: name.to_owned(),
+                kana: kana.to_owned(),
+            });
+        }
+
+        let mut female_first_names = vec![];
+        let mut male_first_names = vec![];
+        for line in FIRST_NAMES.lines().filter(|line| !line.is_empty()) {
+            let columns = line.split('\t').collect::<Vec<&str>>();
+            anyhow::ensure!(columns.len() == 3, "invalid first name entry: {line}");
+            let entry = Entry {
+                name: columns[0].to_owned(),
+                kana: columns[1].to_owned(),
+            };
+            match columns[2].parse::<Sex>()? {
+                Sex::Female => female_first_names.push(entry),
+                Sex::Male => male_first_names.push(entry),
+            }
+        }
+
+        Ok(Self {
+            last_names,
+            female_first_names,
+            male_first_names,
+        })
+    }
+
+    fn first_names(&self, sex: Sex) -> &[Entry] {
+        match sex {
+            Sex::Female => &self.female_first_names,
+            Sex::Male => &self.male_first_names,
+        }
+    }
+}
+
+/// Entries are in descending order of popularity and the first half is common.
+/// The dictionary has only popular names, so none of them is rare.
+fn by_rarity(entries: &[Entry], rarity: Rarity) -> Result<&[Entry], GenNameError> {
+    match rarity {
+        Rarity::Any => Ok(entries),
+        Rarity::Common => Ok(&entries[..entries.len() / 2]),
+        Rarity::Rare => Err(GenNameError::UnsupportedRarity(rarity)),
+    }
+}
+
+/// A `NameGenerator` backed by a dictionary compiled into the binary.
+///
+/// It never touches the network, so it works in air-gapped environments.
+#[derive(Clone, Debug, Default)]
+pub struct NameDictionary {
+    rarity: Rarity,
+}
+
+impl NameDictionary {
+    pub fn new(rarity: Rarity) -> Self {
+        Self { rarity }
+    }
+}
+
+#[async_trait::async_trait]
+impl NameGenerator for NameDictionary {
+    async fn generate(
+        &self,
+        sex: Sex,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<Name, GenNameError> {
+        let mut names = self
+            .generate_many(sex, 1, &NameFilter::default(), rng)
+            .await?;
+        Ok(names.pop().expect("names.len() == 1"))
+    }
+
+    async fn generate_many(
+        &self,
+        sex: Sex,
+        count: usize,
+        filter: &NameFilter,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<Vec<Name>, GenNameError> {
+        let dictionary = Dictionary::get();
+        // last names and first names are combined freely, so filter them separately
+        let last_names = by_rarity(&dictionary.last_names, self.rarity)?
+            .iter()
+            .filter(|entry| filter.matches_last_name(&entry.name, &entry.kana))
+            .collect::<Vec<&Entry>>();
+        let first_names = by_rarity(dictionary.first_names(sex), self.rarity)?
+            .iter()
+            .filter(|entry| filter.matches_first_name(&entry.name, &entry.kana))
+            .collect::<Vec<&Entry>>();
+        if last_names.is_empty() || first_names.is_empty() {
+            return Err(GenNameError::NoMatch);
+        }
+        Ok((0..count)
+            .map(|_| {
+                let last_name = last_names.choose(rng).expect("last_names is not empty");
+                let first_name = first_names.choose(rng).expect("first_names is not empty");
+                Name {
+                    first_name: first_name.name.clone(),
+                    first_name_kana: first_name.kana.clone(),
+                    last_name: last_name.name.clone(),
+                    last_name_kana: last_name.kana.clone(),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        let dictionary = Dictionary::parse()?;
+        assert!(!dictionary.last_names.is_empty());
+        assert!(!dictionary.female_first_names.is_empty());
+        assert!(!dictionary.male_first_names.is_empty());
+        for entry in dictionary
+            .last_names
+            .iter()
+            .chain(dictionary.female_first_names.iter())
+            .chain(dictionary.male_first_names.iter())
+        {
+            assert!(
+                entry
+                    .kana
+                    .chars()
+                    .all(|c| (0x3041..=0x3096).contains(&(c as u32))),
+                "{} is not hiragana",
+                entry.kana
+            );
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate() -> anyhow::Result<()> {
+        let dictionary = Dictionary::get();
+        let name = NameDictionary::default()
+            .generate(Sex::Female, &mut StdRng::from_entropy())
+            .await?;
+        assert!(dictionary
+            .female_first_names
+            .iter()
+            .any(|entry| entry.name == name.first_name && entry.kana == name.first_name_kana));
+        assert!(dictionary
+            .last_names
+            .iter()
+            .any(|entry| entry.name == name.last_name && entry.kana == name.last_name_kana));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_by_rarity() -> anyhow::Result<()> {
+        let dictionary = Dictionary::get();
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let name = NameDictionary::new(Rarity::Common)
+                .generate(Sex::Male, &mut rng)
+                .await?;
+            let index = dictionary
+                .last_names
+                .iter()
+                .position(|entry| entry.name == name.last_name)
+                .expect("in the dictionary");
+            assert!(index < dictionary.last_names.len() / 2, "{index}");
+        }
+        assert!(matches!(
+            NameDictionary::new(Rarity::Rare)
+                .generate(Sex::Male, &mut rng)
+                .await,
+            Err(GenNameError::UnsupportedRarity(Rarity::Rare))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_with_filter() -> anyhow::Result<()> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let filter = "last_name:contains:田,first_name_kana:length:3".parse::<NameFilter>()?;
+        let names = NameDictionary::default()
+            .generate_many(Sex::Female, 20, &filter, &mut rng)
+            .await?;
+        assert_eq!(names.len(), 20);
+        assert!(names.iter().all(|name| filter.matches(name)));
+
+        let filter = "last_name:contains:𠮷".parse::<NameFilter>()?;
+        assert!(matches!(
+            NameDictionary::default()
+                .generate_many(Sex::Female, 1, &filter, &mut rng)
+                .await,
+            Err(GenNameError::NoMatch)
+        ));
+        Ok(())
+    }
+}
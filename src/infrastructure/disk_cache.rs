@@ -0,0 +1,145 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Context;
+
+use crate::model::{Name, Rarity, Sex};
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct CacheFile {
+    /// Seconds since the Unix epoch
+    fetched_at: u64,
+    names: Vec<Name>,
+}
+
+/// Scraped names stored in `<dir>/names-<sex>.json`, or `<dir>/names-<sex>-<rarity>.json`
+/// unless the rarity is `Rarity::Any`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// `$XDG_CACHE_HOME/genpi` or `~/.cache/genpi` on Linux
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("genpi"))
+    }
+
+    fn path(&self, sex: Sex, rarity: Rarity) -> PathBuf {
+        let sex = match sex {
+            Sex::Female => "female",
+            Sex::Male => "male",
+        };
+        match rarity {
+            Rarity::Any => self.dir.join(format!("names-{sex}.json")),
+            _ => self.dir.join(format!("names-{sex}-{}.json", rarity.name())),
+        }
+    }
+
+    /// Returns `None` if there is no cache
+    pub fn load(
+        &self,
+        sex: Sex,
+        rarity: Rarity,
+    ) -> anyhow::Result<Option<(SystemTime, Vec<Name>)>> {
+        let path = self.path(sex, rarity);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let s = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let file = serde_json::from_str::<CacheFile>(&s)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        let fetched_at = SystemTime::UNIX_EPOCH + Duration::from_secs(file.fetched_at);
+        // the file may have been edited or written by an older version
+        let names = file
+            .names
+            .into_iter()
+            .filter(|name| match name.validate() {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!(
+                        "skip {} {} in {}: {e}",
+                        name.last_name,
+                        name.first_name,
+                        path.display()
+                    );
+                    false
+                }
+            })
+            .collect();
+        Ok(Some((fetched_at, names)))
+    }
+
+    pub fn store(
+        &self,
+        sex: Sex,
+        rarity: Rarity,
+        fetched_at: SystemTime,
+        names: &[Name],
+    ) -> anyhow::Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("failed to create {}", self.dir.display()))?;
+        let file = CacheFile {
+            fetched_at: fetched_at.duration_since(SystemTime::UNIX_EPOCH)?.as_secs(),
+            names: names.to_vec(),
+        };
+        let path = self.path(sex, rarity);
+        // write to a temporary file and rename it not to leave a broken cache
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string(&file)?)
+            .with_context(|| format!("failed to write {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, &path)
+            .with_context(|| format!("failed to rename {}", tmp_path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_and_store() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cache = DiskCache::new(dir.path().join("genpi"));
+        assert!(cache.load(Sex::Female, Rarity::Any)?.is_none());
+
+        let names = vec![
+            Name {
+                first_name: "花子".to_owned(),
+                first_name_kana: "はなこ".to_owned(),
+                last_name: "山田".to_owned(),
+                last_name_kana: "やまだ".to_owned(),
+            },
+            // invalid names are dropped
+            Name {
+                first_name: "一郎".to_owned(),
+                first_name_kana: "イチロウ".to_owned(),
+                last_name: "鈴木".to_owned(),
+                last_name_kana: "すずき".to_owned(),
+            },
+        ];
+        let fetched_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        cache.store(Sex::Female, Rarity::Any, fetched_at, &names)?;
+        let (loaded_at, loaded) = cache
+            .load(Sex::Female, Rarity::Any)?
+            .expect("cache is stored");
+        assert_eq!(loaded_at, fetched_at);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].first_name, "花子");
+        assert!(cache.load(Sex::Female, Rarity::Rare)?.is_none());
+        assert!(cache.load(Sex::Male, Rarity::Any)?.is_none());
+
+        fs::write(cache.path(Sex::Male, Rarity::Any), "broken")?;
+        assert!(cache.load(Sex::Male, Rarity::Any).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,345 @@
+use std::{path::PathBuf, str::FromStr, time::Duration};
+
+use anyhow::Context;
+use rand::RngCore;
+use tracing::Instrument;
+
+use crate::{
+    infrastructure::{
+        CacheMode, NameDictionary, NamegenClient, NamegenClientOptions, NamesCache,
+        NamesCacheOptions, NamesFile,
+    },
+    model::{GenNameError, HasNameGenerator, Name, NameFilter, NameGenerator, Rarity, Sex},
+};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NameSourceKind {
+    /// Scrape names from namegen.jp
+    Namegen,
+    /// Use the names scraped before and cached on disk, even if they have expired
+    Cache,
+    /// Use the dictionary embedded in the binary
+    Dictionary,
+    /// Use the names file given by `--names-file` or `NAMES_FILE`
+    File,
+}
+
+impl NameSourceKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Namegen => "namegen",
+            Self::Cache => "cache",
+            Self::Dictionary => "dictionary",
+            Self::File => "file",
+        }
+    }
+}
+
+impl FromStr for NameSourceKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "namegen" => Ok(Self::Namegen),
+            "cache" => Ok(Self::Cache),
+            "dictionary" => Ok(Self::Dictionary),
+            "file" => Ok(Self::File),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not a valid name source (namegen|cache|dictionary|file)"
+            )),
+        }
+    }
+}
+
+/// Name sources in the order to try, e.g. `namegen,cache,dictionary`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NameSourceKinds(Vec<NameSourceKind>);
+
+impl Default for NameSourceKinds {
+    fn default() -> Self {
+        Self(vec![
+            NameSourceKind::Namegen,
+            NameSourceKind::Cache,
+            NameSourceKind::Dictionary,
+        ])
+    }
+}
+
+impl FromStr for NameSourceKinds {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let kinds = s
+            .split(',')
+            .map(|s| s.trim().parse::<NameSourceKind>())
+            .collect::<anyhow::Result<Vec<NameSourceKind>>>()?;
+        Ok(Self(kinds))
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Source {
+    Namegen(NamesCache),
+    Dictionary(NameDictionary),
+    File(NamesFile),
+}
+
+impl Source {
+    async fn generate_many(
+        &self,
+        sex: Sex,
+        count: usize,
+        filter: &NameFilter,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<Vec<Name>, GenNameError> {
+        match self {
+            Self::Namegen(generator) => generator.generate_many(sex, count, filter, rng).await,
+            Self::Dictionary(generator) => generator.generate_many(sex, count, filter, rng).await,
+            Self::File(generator) => generator.generate_many(sex, count, filter, rng).await,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NameSourceOptions {
+    pub kinds: NameSourceKinds,
+    /// Tried first unless `kinds` has `NameSourceKind::File`
+    pub names_file: Option<PathBuf>,
+    /// Ignored by `NameSourceKind::File`
+    pub rarity: Rarity,
+    /// Used only by `NameSourceKind::Namegen` and `NameSourceKind::Cache`
+    pub client: NamegenClientOptions,
+    /// Used only by `NameSourceKind::Namegen` and `NameSourceKind::Cache`
+    pub cache: NamesCacheOptions,
+}
+
+/// A `NameGenerator` that falls back to the next source when a source fails.
+/// Names not matching the filter are not a failure of the source
+#[derive(Clone, Debug)]
+pub struct NameSource {
+    sources: Vec<(NameSourceKind, Source)>,
+}
+
+impl NameSource {
+    pub fn new(options: NameSourceOptions) -> anyhow::Result<Self> {
+        let NameSourceOptions {
+            kinds: NameSourceKinds(mut kinds),
+            names_file,
+            rarity,
+            client,
+            cache: cache_options,
+        } = options;
+        if names_file.is_some() && !kinds.contains(&NameSourceKind::File) {
+            kinds.insert(0, NameSourceKind::File);
+        }
+        let names_file = match names_file {
+            Some(path) => Some(NamesFile::read(&path)?),
+            None => None,
+        };
+        let client = NamegenClient::new(client)?;
+        let sources = kinds
+            .into_iter()
+            .map(|kind| {
+                let source = match kind {
+                    NameSourceKind::Namegen => Source::Namegen(NamesCache::new(
+                        client.clone(),
+                        rarity,
+                        cache_options.clone(),
+                    )),
+                    NameSourceKind::Cache => Source::Namegen(NamesCache::new(
+                        client.clone(),
+                        rarity,
+                        NamesCacheOptions {
+                            mode: CacheMode::Offline,
+                            ..cache_options.clone()
+                        },
+                    )),
+                    NameSourceKind::Dictionary => Source::Dictionary(NameDictionary::new(rarity)),
+                    NameSourceKind::File => Source::File(
+                        names_file
+                            .clone()
+                            .context("the file name source requires a names file")?,
+                    ),
+                };
+                Ok((kind, source))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self { sources })
+    }
+
+    /// Pre-fetches names for the namegen sources
+    pub fn spawn_prefetch(&self, interval: Duration) {
+        for (kind, source) in &self.sources {
+            if let (NameSourceKind::Namegen, Source::Namegen(cache)) = (kind, source) {
+                cache.spawn_prefetch(interval);
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl NameGenerator for NameSource {
+    async fn generate(
+        &self,
+        sex: Sex,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<Name, GenNameError> {
+        let mut names = self
+            .generate_many(sex, 1, &NameFilter::default(), rng)
+            .await?;
+        Ok(names.pop().expect("names.len() == 1"))
+    }
+
+    async fn generate_many(
+        &self,
+        sex: Sex,
+        count: usize,
+        filter: &NameFilter,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<Vec<Name>, GenNameError> {
+        Ok(self
+            .generate_many_with_source(sex, count, filter, rng)
+            .await?
+            .0)
+    }
+
+    async fn generate_many_with_source(
+        &self,
+        sex: Sex,
+        count: usize,
+        filter: &NameFilter,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<(Vec<Name>, Option<&'static str>), GenNameError> {
+        let mut last_error = None;
+        for (kind, source) in &self.sources {
+            let span = tracing::info_span!("name_source", source = kind.name(), ?sex, count);
+            match source
+                .generate_many(sex, count, filter, rng)
+                .instrument(span)
+                .await
+            {
+                Ok(names) => {
+                    tracing::debug!(source = kind.name(), "generated names");
+                    return Ok((names, Some(kind.name())));
+                }
+                // the next source would change the names without fixing the request
+                Err(e @ GenNameError::NoMatch) => return Err(e),
+                Err(e) => {
+                    tracing::warn!(source = kind.name(), "fall back to the next source: {e}");
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.expect("at least one name source"))
+    }
+}
+
+impl HasNameGenerator for NameSource {
+    type NameGenerator = Self;
+
+    fn name_generator(&self) -> &Self::NameGenerator {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::infrastructure::DiskCache;
+
+    use super::*;
+
+    #[test]
+    fn test_name_source_kind_from_str() -> anyhow::Result<()> {
+        assert_eq!(
+            "namegen".parse::<NameSourceKind>()?,
+            NameSourceKind::Namegen
+        );
+        assert_eq!("cache".parse::<NameSourceKind>()?, NameSourceKind::Cache);
+        assert_eq!(
+            "dictionary".parse::<NameSourceKind>()?,
+            NameSourceKind::Dictionary
+        );
+        assert_eq!(
+            "foo".parse::<NameSourceKind>().unwrap_err().to_string(),
+            "foo is not a valid name source (namegen|cache|dictionary|file)"
+        );
+        assert_eq!(
+            "namegen, dictionary".parse::<NameSourceKinds>()?,
+            NameSourceKinds(vec![NameSourceKind::Namegen, NameSourceKind::Dictionary])
+        );
+        assert!("namegen,,dictionary".parse::<NameSourceKinds>().is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fallback() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cache_options = NamesCacheOptions {
+            disk_cache: Some(DiskCache::new(dir.path().to_path_buf())),
+            ..Default::default()
+        };
+        let name_source = NameSource::new(NameSourceOptions {
+            kinds: "cache,dictionary".parse()?,
+            cache: cache_options.clone(),
+            ..Default::default()
+        })?;
+        let mut rng = StdRng::seed_from_u64(0);
+        let (names, source) = name_source
+            .generate_many_with_source(Sex::Female, 3, &NameFilter::default(), &mut rng)
+            .await?;
+        assert_eq!(names.len(), 3);
+        assert_eq!(source, Some("dictionary"));
+        let filter = "last_name:contains:田".parse::<NameFilter>()?;
+        let (names, _) = name_source
+            .generate_many_with_source(Sex::Female, 3, &filter, &mut rng)
+            .await?;
+        assert!(names.iter().all(|name| filter.matches(name)));
+
+        let name_source = NameSource::new(NameSourceOptions {
+            kinds: "cache".parse()?,
+            cache: cache_options,
+            ..Default::default()
+        })?;
+        assert!(matches!(
+            name_source.generate(Sex::Male, &mut rng).await,
+            Err(GenNameError::Offline)
+        ));
+
+        // no match in the names file is not a failure of the source
+        let names_file = dir.path().join("names.csv");
+        std::fs::write(
+            &names_file,
+            "last_name,last_name_kana,first_name,first_name_kana,sex\n\
+             山田,やまだ,花子,はなこ,female\n",
+        )?;
+        let name_source = NameSource::new(NameSourceOptions {
+            kinds: "file,dictionary".parse()?,
+            names_file: Some(names_file),
+            ..Default::default()
+        })?;
+        let filter = "last_name:contains:佐".parse::<NameFilter>()?;
+        assert!(matches!(
+            name_source
+                .generate_many_with_source(Sex::Female, 1, &filter, &mut rng)
+                .await,
+            Err(GenNameError::NoMatch)
+        ));
+        let (_, source) = name_source
+            .generate_many_with_source(Sex::Female, 1, &NameFilter::default(), &mut rng)
+            .await?;
+        assert_eq!(source, Some("file"));
+
+        assert_eq!(
+            NameSource::new(NameSourceOptions {
+                kinds: "file".parse()?,
+                ..Default::default()
+            })
+            .unwrap_err()
+            .to_string(),
+            "the file name source requires a names file"
+        );
+        Ok(())
+    }
+}
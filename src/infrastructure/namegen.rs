@@ -1,93 +1,365 @@
 use std::{
-    sync::Arc,
-    time::{Duration, Instant},
+    collections::HashSet,
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
 };
 
-use anyhow::{bail, ensure, Context};
-use rand::{thread_rng, Rng};
+use anyhow::{ensure, Context};
+use rand::{Rng, RngCore};
 use scraper::{Html, Selector};
-use time::{Date, Month, OffsetDateTime};
-use tokio::sync::Mutex;
+use tokio::{sync::Mutex, task::JoinHandle, time::MissedTickBehavior};
 
 use crate::{
-    model::{DateOfBirth, GenNameError, GenPiError, KanaForm, Name, NameGenerator, Sex, PI},
-    use_case::GeneratePiUseCase,
+    infrastructure::{DiskCache, NamegenClient},
+    model::{GenNameError, Name, NameField, NameFilter, NameGenerator, Rarity, Sex},
 };
 
 type Names = Vec<Name>;
 
+const NAMEGEN_URL: &str = "https://namegen.jp/";
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CacheMode {
+    /// Use the cache until it expires
+    #[default]
+    Normal,
+    /// Ignore the disk cache and scrape names again
+    Refresh,
+    /// Never scrape names. Use the disk cache even if it has expired
+    Offline,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NamesCacheOptions {
+    /// `None` disables the disk cache
+    pub disk_cache: Option<DiskCache>,
+    pub mode: CacheMode,
+    /// The maximum number of names kept per sex
+    pub pool_size: usize,
+    pub ttl: Duration,
+}
+
+impl NamesCacheOptions {
+    pub const DEFAULT_POOL_SIZE: usize = 1000;
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+}
+
+impl Default for NamesCacheOptions {
+    fn default() -> Self {
+        Self {
+            disk_cache: DiskCache::default_dir().map(DiskCache::new),
+            mode: CacheMode::default(),
+            pool_size: Self::DEFAULT_POOL_SIZE,
+            ttl: Self::DEFAULT_TTL,
+        }
+    }
+}
+
+/// Names of a sex and the lock held while they are refreshed
+#[derive(Debug, Default)]
+struct NamesSlot {
+    names: RwLock<Option<(SystemTime, Arc<Names>)>>,
+    refresh: Arc<Mutex<()>>,
+}
+
 #[derive(Clone, Debug)]
 pub struct NamesCache {
-    female_names: Arc<Mutex<Option<(Instant, Names)>>>,
-    male_names: Arc<Mutex<Option<(Instant, Names)>>>,
+    client: NamegenClient,
+    female_names: Arc<NamesSlot>,
+    male_names: Arc<NamesSlot>,
+    options: NamesCacheOptions,
+    rarity: Rarity,
+    /// `NAMEGEN_URL` except in tests
+    url: String,
 }
 
 #[async_trait::async_trait]
 impl NameGenerator for NamesCache {
-    async fn generate(&self, sex: Sex) -> Result<Name, GenNameError> {
-        gen_name(self, sex).await
+    async fn generate(
+        &self,
+        sex: Sex,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<Name, GenNameError> {
+        let mut names = gen_name(self, sex, 1, &NameFilter::default(), rng).await?;
+        Ok(names.pop().expect("names.len() == 1"))
+    }
+
+    async fn generate_many(
+        &self,
+        sex: Sex,
+        count: usize,
+        filter: &NameFilter,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<Vec<Name>, GenNameError> {
+        gen_name(self, sex, count, filter, rng).await
     }
 }
 
-impl Default for NamesCache {
-    fn default() -> Self {
+impl NamesCache {
+    pub fn new(client: NamegenClient, rarity: Rarity, options: NamesCacheOptions) -> Self {
         Self {
-            female_names: Arc::new(Mutex::new(None)),
-            male_names: Arc::new(Mutex::new(None)),
+            client,
+            female_names: Arc::default(),
+            male_names: Arc::default(),
+            options,
+            rarity,
+            url: NAMEGEN_URL.to_owned(),
         }
     }
+
+    fn slot(&self, sex: Sex) -> &NamesSlot {
+        match sex {
+            Sex::Female => &self.female_names,
+            Sex::Male => &self.male_names,
+        }
+    }
+
+    fn snapshot(&self, sex: Sex) -> Option<(SystemTime, Arc<Names>)> {
+        self.slot(sex)
+            .names
+            .read()
+            .expect("names lock is not poisoned")
+            .clone()
+    }
+
+    fn is_expired(&self, fetched_at: SystemTime) -> bool {
+        if self.options.mode == CacheMode::Offline {
+            return false;
+        }
+        match fetched_at.elapsed() {
+            Ok(elapsed) => elapsed > self.options.ttl,
+            // fetched in the future
+            Err(_) => false,
+        }
+    }
+
+    /// Waits for the refresh in flight, if any, instead of starting another one
+    async fn refresh(&self, sex: Sex) -> Result<Arc<Names>, GenNameError> {
+        let _guard = self.slot(sex).refresh.lock().await;
+        // the names may have been refreshed while waiting for the lock
+        match self.snapshot(sex) {
+            Some((fetched_at, names)) if !self.is_expired(fetched_at) => Ok(names),
+            _ => self.refresh_locked(sex).await,
+        }
+    }
+
+    /// Refreshes the names in the background unless a refresh is already in flight
+    fn spawn_refresh(&self, sex: Sex) {
+        let Ok(guard) = self.slot(sex).refresh.clone().try_lock_owned() else {
+            return;
+        };
+        let cache = self.clone();
+        tokio::spawn(async move {
+            let _guard = guard;
+            if let Err(e) = cache.refresh_locked(sex).await {
+                tracing::warn!("failed to refresh names: {e}");
+            }
+        });
+    }
+
+    /// Scrapes names of both sexes every `interval` to grow the pools
+    pub fn spawn_prefetch(&self, interval: Duration) -> JoinHandle<()> {
+        let cache = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                for sex in [Sex::Female, Sex::Male] {
+                    let _guard = cache.slot(sex).refresh.lock().await;
+                    match cache.scrape_locked(sex).await {
+                        Ok(names) => tracing::debug!("prefetched names: {} in pool", names.len()),
+                        Err(e) => tracing::warn!("failed to prefetch names: {e}"),
+                    }
+                }
+            }
+        })
+    }
+
+    /// Loads names from the disk cache, or scrapes them.
+    /// Must be called while holding the refresh lock of `sex`
+    async fn refresh_locked(&self, sex: Sex) -> Result<Arc<Names>, GenNameError> {
+        if let (Some(disk_cache), false) = (
+            &self.options.disk_cache,
+            self.options.mode == CacheMode::Refresh,
+        ) {
+            match disk_cache.load(sex, self.rarity) {
+                Ok(Some((fetched_at, names)))
+                    if !names.is_empty() && !self.is_expired(fetched_at) =>
+                {
+                    return Ok(self.update(sex, fetched_at, names));
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("ignore the disk cache: {e:#}"),
+            }
+        }
+        self.scrape_locked(sex).await
+    }
+
+    /// Scrapes names matching `filter` for a single request.
+    /// They are not added to the pool lest they skew unfiltered requests
+    async fn scrape_matching(&self, sex: Sex, filter: &NameFilter) -> Result<Names, GenNameError> {
+        gen_names(&self.client, &self.url, sex, self.rarity, filter).await
+    }
+
+    /// Scrapes names, adds them to the pool and stores the pool in the disk cache.
+    /// Must be called while holding the refresh lock of `sex`
+    async fn scrape_locked(&self, sex: Sex) -> Result<Arc<Names>, GenNameError> {
+        if self.options.mode == CacheMode::Offline {
+            return Err(GenNameError::Offline);
+        }
+        let fetched_at = SystemTime::now();
+        let names = gen_names(
+            &self.client,
+            &self.url,
+            sex,
+            self.rarity,
+            &NameFilter::default(),
+        )
+        .await?;
+        let names = self.update(sex, fetched_at, names);
+        if let Some(disk_cache) = &self.options.disk_cache {
+            if let Err(e) = disk_cache.store(sex, self.rarity, fetched_at, &names) {
+                tracing::warn!("failed to store the disk cache: {e:#}");
+            }
+        }
+        Ok(names)
+    }
+
+    /// Merges `names` into the pool of `sex`
+    fn update(&self, sex: Sex, fetched_at: SystemTime, names: Names) -> Arc<Names> {
+        let mut locked = self
+            .slot(sex)
+            .names
+            .write()
+            .expect("names lock is not poisoned");
+        let old_names = locked.as_ref().map(|(_, names)| names.as_slice());
+        let names = Arc::new(merge(
+            names,
+            old_names.unwrap_or_default(),
+            self.options.pool_size,
+        ));
+        *locked = Some((fetched_at, Arc::clone(&names)));
+        names
+    }
 }
 
-fn choose<T>(a: &[T]) -> &T {
-    &a[thread_rng().gen_range(0..a.len())]
+/// Returns deduplicated names, the newest first, up to `pool_size`
+fn merge(new_names: Names, old_names: &[Name], pool_size: usize) -> Names {
+    let mut seen = HashSet::new();
+    new_names
+        .into_iter()
+        .chain(old_names.iter().cloned())
+        .filter(|name| seen.insert(name.clone()))
+        .take(pool_size)
+        .collect()
 }
 
-async fn gen_name(cache: &NamesCache, sex: Sex) -> Result<Name, GenNameError> {
-    let mut locked = match sex {
-        Sex::Female => cache
-            .female_names
-            .try_lock()
-            .map_err(|_| GenNameError::Conflict)?,
-        Sex::Male => cache
-            .male_names
-            .try_lock()
-            .map_err(|_| GenNameError::Conflict)?,
-    };
-    let name = match locked.as_mut() {
-        Some((instant, names)) => {
-            if instant.elapsed() > Duration::new(5, 0) {
-                *instant = Instant::now();
-                *names = gen_names(sex)
-                    .await
-                    .map_err(|_| GenNameError::RequestFailure)?
+fn choose<'a, T>(a: &'a [T], rng: &mut (dyn RngCore + Send)) -> &'a T {
+    &a[rng.gen_range(0..a.len())]
+}
+
+fn matching<'a>(names: &'a [Name], filter: &NameFilter) -> Vec<&'a Name> {
+    names.iter().filter(|name| filter.matches(name)).collect()
+}
+
+async fn gen_name(
+    cache: &NamesCache,
+    sex: Sex,
+    count: usize,
+    filter: &NameFilter,
+    rng: &mut (dyn RngCore + Send),
+) -> Result<Vec<Name>, GenNameError> {
+    // serve stale names while they are refreshed
+    let names = match cache.snapshot(sex) {
+        Some((fetched_at, names)) => {
+            if cache.is_expired(fetched_at) {
+                cache.spawn_refresh(sex);
             }
-            choose(names).clone()
-        }
-        None => {
-            let instant = Instant::now();
-            let names = gen_names(sex)
-                .await
-                .map_err(|_| GenNameError::RequestFailure)?;
-            let name = choose(&names).clone();
-            *locked = Some((instant, names));
-            name
+            names
         }
+        None => cache.refresh(sex).await?,
     };
-    Ok(name)
+    let mut candidates = matching(&names, filter);
+    // scrape names matching the filter if the pool has none
+    let scraped;
+    if candidates.is_empty() {
+        if filter.is_empty() {
+            return Err(GenNameError::NoNames);
+        }
+        if cache.options.mode == CacheMode::Offline {
+            return Err(GenNameError::NoMatch);
+        }
+        scraped = cache.scrape_matching(sex, filter).await?;
+        candidates = matching(&scraped, filter);
+        if candidates.is_empty() {
+            return Err(GenNameError::NoMatch);
+        }
+    }
+    // all names in a batch are chosen from the same fill
+    Ok((0..count)
+        .map(|_| (*choose(&candidates, rng)).clone())
+        .collect())
+}
+
+/// `(rarity, condition)` of the namegen.jp query.
+/// namegen.jp rates names from 1 (the most common) and compares with
+/// `ika` (less than or equal to) or `ijou` (greater than or equal to).
+fn rarity_query(rarity: Rarity) -> (&'static str, &'static str) {
+    match rarity {
+        Rarity::Any => ("", "ika"),
+        Rarity::Common => ("2", "ika"),
+        Rarity::Rare => ("4", "ijou"),
+    }
 }
 
-async fn gen_names(sex: Sex) -> anyhow::Result<Names> {
+fn namegen_url(base_url: &str, sex: Sex, rarity: Rarity, filter: &NameFilter) -> reqwest::Url {
     let sex = match sex {
         Sex::Female => "female",
         Sex::Male => "male",
     };
-    let url = format!("https://namegen.jp/?country=japan&sex={sex}&middlename=&middlename_cond=fukumu&middlename_rarity=&middlename_rarity_cond=ika&lastname=&lastname_cond=fukumu&lastname_rarity=&lastname_rarity_cond=ika&lastname_type=name&firstname=&firstname_cond=fukumu&firstname_rarity=&firstname_rarity_cond=ika&firstname_type=name");
-    let response = reqwest::get(url).await?;
-    if !response.status().is_success() {
-        bail!("Error: {}", response.status());
-    }
-    let response_body = response.text().await?;
-    let html = Html::parse_document(&response_body);
+    let (rarity, rarity_cond) = rarity_query(rarity);
+    // namegen.jp searches only kanji by `contains`. The other conditions are applied to the result
+    let last_name = filter.contains(NameField::LastName).unwrap_or_default();
+    let first_name = filter.contains(NameField::FirstName).unwrap_or_default();
+    reqwest::Url::parse_with_params(
+        base_url,
+        [
+            ("country", "japan"),
+            ("sex", sex),
+            ("middlename", ""),
+            ("middlename_cond", "fukumu"),
+            ("middlename_rarity", ""),
+            ("middlename_rarity_cond", "ika"),
+            ("lastname", last_name),
+            ("lastname_cond", "fukumu"),
+            ("lastname_rarity", rarity),
+            ("lastname_rarity_cond", rarity_cond),
+            ("lastname_type", "name"),
+            ("firstname", first_name),
+            ("firstname_cond", "fukumu"),
+            ("firstname_rarity", rarity),
+            ("firstname_rarity_cond", rarity_cond),
+            ("firstname_type", "name"),
+        ],
+    )
+    .expect("base_url is valid")
+}
+
+async fn gen_names(
+    client: &NamegenClient,
+    base_url: &str,
+    sex: Sex,
+    rarity: Rarity,
+    filter: &NameFilter,
+) -> Result<Names, GenNameError> {
+    let url = namegen_url(base_url, sex, rarity, filter);
+    let response_body = client.get(url.as_str()).await?;
+    parse_names(&response_body).map_err(|e| GenNameError::Parse(format!("{e:#}")))
+}
+
+fn parse_names(response_body: &str) -> anyhow::Result<Names> {
+    let html = Html::parse_document(response_body);
     let table_selector =
         Selector::parse("table.gen-table-1").expect("selector 'table.gen-table-1' is valid");
     let table = html
@@ -123,38 +395,225 @@ async fn gen_names(sex: Sex) -> anyhow::Result<Names> {
             .collect::<Vec<String>>();
         ensure!(sei_mei_kana.len() == 2, "sei_mei_kana.len() != 2");
 
-        names.push(Name {
+        let name = Name {
             first_name: sei_mei[1].clone(),
             first_name_kana: sei_mei_kana[1].clone(),
             last_name: sei_mei[0].clone(),
             last_name_kana: sei_mei_kana[0].clone(),
-        });
+        };
+        // kana other than hiragana cannot be converted to katakana
+        match name.validate() {
+            Ok(()) => names.push(name),
+            Err(e) => tracing::warn!("skip {} {}: {e}", name.last_name, name.first_name),
+        }
     }
     Ok(names)
 }
 
-#[async_trait::async_trait]
-impl GeneratePiUseCase for NamesCache {
-    // <https://github.com/tokio-rs/tracing/issues/2876>
-    #[tracing::instrument(skip(self), err, ret)]
-    #[allow(clippy::blocks_in_conditions)]
-    async fn generate_pi(&self, kana_form: KanaForm) -> Result<PI, GenPiError> {
-        let mut rng = rand::rngs::OsRng;
-        let sex = rng.gen::<Sex>();
-        let name = NameGenerator::generate(self, sex)
-            .await
-            .map_err(GenPiError::GenNameError)?;
-        let name = match kana_form {
-            KanaForm::Hiragana => name,
-            KanaForm::Katakana => name.in_katakana(),
-            KanaForm::HalfwidthKana => name.in_halfwidth_kana(),
-        };
-        let current_year = OffsetDateTime::now_utc().year();
-        let start =
-            Date::from_calendar_date(current_year - 120, Month::January, 1).expect("invalid date");
-        let end =
-            Date::from_calendar_date(current_year, Month::December, 31).expect("invalid date");
-        let date = rng.gen_range(DateOfBirth::from(start)..=DateOfBirth::from(end));
-        Ok(PI::from((name, sex, date)))
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn name(last_name: &str, first_name: &str) -> Name {
+        Name {
+            first_name: first_name.to_owned(),
+            first_name_kana: "".to_owned(),
+            last_name: last_name.to_owned(),
+            last_name_kana: "".to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let old_names = vec![
+            name("山田", "花子"),
+            name("佐藤", "健"),
+            name("鈴木", "一郎"),
+        ];
+        let new_names = vec![name("田中", "結衣"), name("佐藤", "健")];
+        assert_eq!(
+            merge(new_names.clone(), &old_names, 10),
+            vec![
+                name("田中", "結衣"),
+                name("佐藤", "健"),
+                name("山田", "花子"),
+                name("鈴木", "一郎")
+            ]
+        );
+        assert_eq!(
+            merge(new_names, &old_names, 3),
+            vec![
+                name("田中", "結衣"),
+                name("佐藤", "健"),
+                name("山田", "花子")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_namegen_url() -> anyhow::Result<()> {
+        assert_eq!(
+            namegen_url(NAMEGEN_URL, Sex::Female, Rarity::Any, &NameFilter::default()).as_str(),
+            "https://namegen.jp/?country=japan&sex=female&middlename=&middlename_cond=fukumu&middlename_rarity=&middlename_rarity_cond=ika&lastname=&lastname_cond=fukumu&lastname_rarity=&lastname_rarity_cond=ika&lastname_type=name&firstname=&firstname_cond=fukumu&firstname_rarity=&firstname_rarity_cond=ika&firstname_type=name"
+        );
+        let url = namegen_url(
+            NAMEGEN_URL,
+            Sex::Male,
+            Rarity::Rare,
+            &"last_name:contains:田,first_name_kana:starts_with:か".parse()?,
+        );
+        let query = url.query_pairs().collect::<Vec<_>>();
+        assert!(query.contains(&("lastname".into(), "田".into())));
+        assert!(query.contains(&("firstname".into(), "".into())));
+        assert!(query.contains(&("lastname_rarity".into(), "4".into())));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_names() -> anyhow::Result<()> {
+        let html = r#"<html><body><table class="gen-table-1">
+            <tr><th>名前</th><th>読み</th></tr>
+            <tr><td class="name">山田 <span>花子</span></td><td class="pron">やまだ はなこ</td></tr>
+            <tr><td class="name">佐藤 <span>健</span></td><td class="pron">さとう けん</td></tr>
+            <tr><td class="name">鈴木 <span>一郎</span></td><td class="pron">スズキ いちろう</td></tr>
+            </table></body></html>"#;
+        let names = parse_names(html)?;
+        assert_eq!(
+            names
+                .iter()
+                .map(|name| format!("{} {}", name.last_name_kana, name.first_name_kana))
+                .collect::<Vec<_>>(),
+            vec!["やまだ はなこ", "さとう けん"]
+        );
+        assert_eq!(names[1].last_name, "佐藤");
+        assert_eq!(names[1].first_name, "健");
+
+        assert!(parse_names("<html><body></body></html>").is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let disk_cache = DiskCache::new(dir.path().to_path_buf());
+        let names = vec![Name {
+            first_name: "花子".to_owned(),
+            first_name_kana: "はなこ".to_owned(),
+            last_name: "山田".to_owned(),
+            last_name_kana: "やまだ".to_owned(),
+        }];
+        disk_cache.store(Sex::Female, Rarity::Any, SystemTime::now(), &names)?;
+        let cache = NamesCache::new(
+            NamegenClient::new(Default::default())?,
+            Rarity::Any,
+            NamesCacheOptions {
+                disk_cache: Some(disk_cache),
+                mode: CacheMode::Offline,
+                pool_size: NamesCacheOptions::DEFAULT_POOL_SIZE,
+                ttl: NamesCacheOptions::DEFAULT_TTL,
+            },
+        );
+
+        let handles = (0..32)
+            .map(|seed| {
+                let cache = cache.clone();
+                tokio::spawn(async move {
+                    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+                    cache
+                        .generate_many(Sex::Female, 10, &NameFilter::default(), &mut rng)
+                        .await
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            assert_eq!(handle.await??.len(), 10);
+        }
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        assert!(matches!(
+            cache
+                .generate_many(
+                    Sex::Female,
+                    1,
+                    &"last_name:contains:田".parse()?,
+                    &mut rng
+                )
+                .await,
+            Ok(names) if names[0].last_name == "山田"
+        ));
+        assert!(matches!(
+            cache
+                .generate_many(Sex::Female, 1, &"last_name:contains:中".parse()?, &mut rng)
+                .await,
+            Err(GenNameError::NoMatch)
+        ));
+        assert!(matches!(
+            cache
+                .generate(Sex::Male, &mut rand::rngs::StdRng::seed_from_u64(0))
+                .await,
+            Err(GenNameError::Offline)
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_filtered_scrape() -> anyhow::Result<()> {
+        const HTML: &str = r#"<html><body><table class="gen-table-1">
+            <tr><th>名前</th><th>読み</th></tr>
+            <tr><td class="name">田中 <span>花子</span></td><td class="pron">たなか はなこ</td></tr>
+            </table></body></html>"#;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(async move {
+            let app = axum::Router::new().route("/", axum::routing::get(|| async { HTML }));
+            axum::serve(listener, app).await
+        });
+
+        let dir = tempfile::tempdir()?;
+        let disk_cache = DiskCache::new(dir.path().to_path_buf());
+        let names = vec![Name {
+            first_name: "花子".to_owned(),
+            first_name_kana: "はなこ".to_owned(),
+            last_name: "山田".to_owned(),
+            last_name_kana: "やまだ".to_owned(),
+        }];
+        let fetched_at = SystemTime::now();
+        disk_cache.store(Sex::Female, Rarity::Any, fetched_at, &names)?;
+        let mut cache = NamesCache::new(
+            NamegenClient::new(Default::default())?,
+            Rarity::Any,
+            NamesCacheOptions {
+                disk_cache: Some(disk_cache.clone()),
+                ..Default::default()
+            },
+        );
+        cache.url = format!("http://{addr}/");
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let filtered = cache
+            .generate_many(Sex::Female, 1, &"last_name:contains:中".parse()?, &mut rng)
+            .await?;
+        assert_eq!(filtered[0].last_name, "田中");
+        let (pool_fetched_at, pool) = cache.snapshot(Sex::Female).expect("pool is loaded");
+        assert_eq!(*pool, names);
+        assert_eq!(
+            pool_fetched_at,
+            disk_cache
+                .load(Sex::Female, Rarity::Any)?
+                .expect("cached")
+                .0
+        );
+        assert_eq!(
+            disk_cache
+                .load(Sex::Female, Rarity::Any)?
+                .map(|(_, names)| names),
+            Some(names)
+        );
+        let unfiltered = cache
+            .generate_many(Sex::Female, 10, &NameFilter::default(), &mut rng)
+            .await?;
+        assert!(unfiltered.iter().all(|name| name.last_name == "山田"));
+        Ok(())
     }
 }
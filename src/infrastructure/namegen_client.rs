@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use rand::Rng;
+
+use crate::model::GenNameError;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NamegenClientOptions {
+    /// Delay before the first retry, doubled on each retry
+    pub backoff: Duration,
+    /// `None` uses the proxy in the environment (e.g. `HTTPS_PROXY`)
+    pub proxy: Option<String>,
+    pub retries: u32,
+    pub timeout: Duration,
+    pub user_agent: String,
+}
+
+impl NamegenClientOptions {
+    pub const DEFAULT_BACKOFF: Duration = Duration::from_millis(500);
+    pub const DEFAULT_RETRIES: u32 = 3;
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+    pub const DEFAULT_USER_AGENT: &'static str =
+        concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+}
+
+impl Default for NamegenClientOptions {
+    fn default() -> Self {
+        Self {
+            backoff: Self::DEFAULT_BACKOFF,
+            proxy: None,
+            retries: Self::DEFAULT_RETRIES,
+            timeout: Self::DEFAULT_TIMEOUT,
+            user_agent: Self::DEFAULT_USER_AGENT.to_owned(),
+        }
+    }
+}
+
+/// HTTP client for namegen.jp that retries with exponential backoff
+#[derive(Clone, Debug)]
+pub struct NamegenClient {
+    backoff: Duration,
+    client: reqwest::Client,
+    retries: u32,
+}
+
+impl NamegenClient {
+    pub fn new(options: NamegenClientOptions) -> anyhow::Result<Self> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(options.timeout)
+            .user_agent(options.user_agent);
+        if let Some(proxy) = options.proxy {
+            builder = builder.proxy(
+                reqwest::Proxy::all(&proxy).with_context(|| format!("invalid proxy: {proxy}"))?,
+            );
+        }
+        Ok(Self {
+            backoff: options.backoff,
+            client: builder.build()?,
+            retries: options.retries,
+        })
+    }
+
+    /// Returns the response body
+    pub async fn get(&self, url: &str) -> Result<String, GenNameError> {
+        let mut attempt = 0;
+        loop {
+            match self.try_get(url).await {
+                Err(e) if attempt < self.retries && is_retryable(&e) => {
+                    let delay = self.delay(attempt);
+                    tracing::warn!("retry in {delay:?}: {e}");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn try_get(&self, url: &str) -> Result<String, GenNameError> {
+        let response = self.client.get(url).send().await.map_err(from_reqwest)?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(GenNameError::Status(status.as_u16()));
+        }
+        response.text().await.map_err(from_reqwest)
+    }
+
+    /// Exponential backoff with jitter, between a half and the whole of `backoff * 2^attempt`
+    fn delay(&self, attempt: u32) -> Duration {
+        let delay = self.backoff.saturating_mul(2u32.saturating_pow(attempt));
+        delay / 2 + delay.mul_f64(rand::thread_rng().gen_range(0.0..=0.5))
+    }
+}
+
+fn from_reqwest(e: reqwest::Error) -> GenNameError {
+    if e.is_timeout() {
+        GenNameError::Timeout
+    } else {
+        GenNameError::Network(e.to_string())
+    }
+}
+
+fn is_retryable(e: &GenNameError) -> bool {
+    match e {
+        GenNameError::Network(_) | GenNameError::Timeout => true,
+        GenNameError::Status(status) => *status == 429 || *status >= 500,
+        GenNameError::Offline
+        | GenNameError::NoNames
+        | GenNameError::NoMatch
+        | GenNameError::UnsupportedRarity(_)
+        | GenNameError::Parse(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay() -> anyhow::Result<()> {
+        let client = NamegenClient::new(NamegenClientOptions {
+            backoff: Duration::from_millis(100),
+            ..Default::default()
+        })?;
+        for (attempt, max) in [(0, 100), (1, 200), (2, 400)] {
+            let delay = client.delay(attempt);
+            assert!(delay >= Duration::from_millis(max / 2), "{delay:?}");
+            assert!(delay <= Duration::from_millis(max), "{delay:?}");
+        }
+        assert!(NamegenClient::new(NamegenClientOptions {
+            proxy: Some("not a url".to_owned()),
+            ..Default::default()
+        })
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(&GenNameError::Timeout));
+        assert!(is_retryable(&GenNameError::Network("reset".to_owned())));
+        assert!(is_retryable(&GenNameError::Status(503)));
+        assert!(is_retryable(&GenNameError::Status(429)));
+        assert!(!is_retryable(&GenNameError::Status(404)));
+        assert!(!is_retryable(&GenNameError::Parse("".to_owned())));
+    }
+}
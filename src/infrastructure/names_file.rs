@@ -0,0 +1,181 @@
+use std::path::Path;
+
+use anyhow::Context;
+use rand::{seq::SliceRandom, RngCore};
+
+use crate::model::{GenNameError, Name, NameFilter, NameGenerator, Sex};
+
+#[derive(Debug, serde::Deserialize)]
+struct NameRecord {
+    last_name: String,
+    last_name_kana: String,
+    first_name: String,
+    first_name_kana: String,
+    sex: Sex,
+}
+
+/// A `NameGenerator` backed by a CSV or JSON file of names supplied by the user
+#[derive(Clone, Debug)]
+pub struct NamesFile {
+    female_names: Vec<Name>,
+    male_names: Vec<Name>,
+}
+
+impl NamesFile {
+    /// Reads a .csv file with a header row or a .json file of an array of objects.
+    /// Both have `last_name`, `last_name_kana`, `first_name`, `first_name_kana` and `sex`.
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let records = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("csv") => csv::Reader::from_reader(s.as_bytes())
+                .deserialize::<NameRecord>()
+                .collect::<Result<Vec<NameRecord>, csv::Error>>()?,
+            Some("json") => serde_json::from_str::<Vec<NameRecord>>(&s)?,
+            _ => anyhow::bail!("names file must be a .csv or .json file"),
+        };
+        Self::from_records(records).with_context(|| format!("invalid names in {}", path.display()))
+    }
+
+    fn from_records(records: Vec<NameRecord>) -> anyhow::Result<Self> {
+        let mut female_names = vec![];
+        let mut male_names = vec![];
+        for (i, record) in records.into_iter().enumerate() {
+            let name = Name {
+                first_name: record.first_name,
+                first_name_kana: record.first_name_kana,
+                last_name: record.last_name,
+                last_name_kana: record.last_name_kana,
+            };
+            name.validate()
+                .with_context(|| format!("record {}", i + 1))?;
+            match record.sex {
+                Sex::Female => female_names.push(name),
+                Sex::Male => male_names.push(name),
+            }
+        }
+        anyhow::ensure!(
+            !female_names.is_empty() || !male_names.is_empty(),
+            "no names"
+        );
+        Ok(Self {
+            female_names,
+            male_names,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl NameGenerator for NamesFile {
+    async fn generate(
+        &self,
+        sex: Sex,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<Name, GenNameError> {
+        let mut names = self
+            .generate_many(sex, 1, &NameFilter::default(), rng)
+            .await?;
+        Ok(names.pop().expect("names.len() == 1"))
+    }
+
+    async fn generate_many(
+        &self,
+        sex: Sex,
+        count: usize,
+        filter: &NameFilter,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<Vec<Name>, GenNameError> {
+        let names = match sex {
+            Sex::Female => &self.female_names,
+            Sex::Male => &self.male_names,
+        };
+        if names.is_empty() {
+            return Err(GenNameError::NoNames);
+        }
+        let names = names
+            .iter()
+            .filter(|name| filter.matches(name))
+            .collect::<Vec<&Name>>();
+        if names.is_empty() {
+            return Err(GenNameError::NoMatch);
+        }
+        Ok((0..count)
+            .map(|_| (*names.choose(rng).expect("names is not empty")).clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let csv_path = dir.path().join("names.csv");
+        std::fs::write(
+            &csv_path,
+            "last_name,last_name_kana,first_name,first_name_kana,sex\n\
+             髙橋,たかはし,𠮷子,よしこ,female\n\
+             勅使河原,てしがわら,寿限無,じゅげむじゅげむごこうのすりきれ,male\n",
+        )?;
+        let names_file = NamesFile::read(&csv_path)?;
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(
+            names_file.generate(Sex::Female, &mut rng).await?.first_name,
+            "𠮷子"
+        );
+        assert_eq!(
+            names_file.generate(Sex::Male, &mut rng).await?.last_name,
+            "勅使河原"
+        );
+
+        let json_path = dir.path().join("names.json");
+        std::fs::write(
+            &json_path,
+            r#"[{"last_name":"齋藤","last_name_kana":"さいとう","first_name":"花子","first_name_kana":"はなこ","sex":"female"}]"#,
+        )?;
+        let names_file = NamesFile::read(&json_path)?;
+        assert_eq!(
+            names_file.generate(Sex::Female, &mut rng).await?.last_name,
+            "齋藤"
+        );
+        assert!(matches!(
+            names_file.generate(Sex::Male, &mut rng).await,
+            Err(GenNameError::NoNames)
+        ));
+        assert!(matches!(
+            names_file
+                .generate_many(Sex::Female, 1, &"last_name:contains:田".parse()?, &mut rng)
+                .await,
+            Err(GenNameError::NoMatch)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_invalid() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("names.csv");
+        std::fs::write(
+            &path,
+            "last_name,last_name_kana,first_name,first_name_kana,sex\n\
+             山田,やまだ,花子,はなこ,female\n\
+             山田,ヤマダ,太郎,たろう,male\n",
+        )?;
+        assert_eq!(
+            format!("{:#}", NamesFile::read(&path).unwrap_err()),
+            format!(
+                "invalid names in {}: record 2: ヤ is not hiragana",
+                path.display()
+            )
+        );
+
+        let path = dir.path().join("names.txt");
+        std::fs::write(&path, "")?;
+        assert!(NamesFile::read(&path).is_err());
+        Ok(())
+    }
+}
@@ -2,35 +2,212 @@ mod config;
 mod handler;
 mod infrastructure;
 mod model;
+mod output;
 mod server;
 mod use_case;
 
+use std::{path::PathBuf, time::Duration};
+
 use anyhow::bail;
 use server::run_server;
 use tracing_subscriber::fmt::format::FmtSpan;
 
-use crate::{infrastructure::NamesCache, model::KanaForm, use_case::GeneratePiUseCase};
+use crate::{
+    infrastructure::{
+        CacheMode, DiskCache, NameSource, NameSourceKinds, NameSourceOptions, NamegenClientOptions,
+        NamesCacheOptions,
+    },
+    model::{
+        AgeDistribution, Charset, DateFormat, DateOfBirth, DateOfBirthConstraints, EmailDomains,
+        FemaleRatio, KanaForm, NameFilter, Normalization, PhoneNumberFormat, PhoneNumberType,
+        Rarity, Romanization, Sex, WarekiFormat, PI,
+    },
+    output::{
+        ColumnNames, Encoding, Fields, OutputFormat, OutputOptions, SqlDialect, SqlOptions,
+        Template,
+    },
+    use_case::{GeneratePiOptions, GeneratePiUseCase},
+};
 
 #[derive(Debug, clap::Parser)]
 struct Cli {
+    /// Add an address (postal code, prefecture, city, town, block number and building)
+    #[arg(long)]
+    address: bool,
+    /// Add the age as of --as-of
+    #[arg(long)]
+    age: bool,
+    /// Distribution of date_of_birth (uniform|population)
+    #[arg(long, default_value = "uniform")]
+    age_distribution: AgeDistribution,
+    /// Date to compute --age and --min-age/--max-age against (YYYY-MM-DD, default: today)
+    #[arg(long, value_name = "DATE")]
+    as_of: Option<DateOfBirth>,
+    /// Generate people born after the date (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE")]
+    born_after: Option<DateOfBirth>,
+    /// Generate people born before the date (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE")]
+    born_before: Option<DateOfBirth>,
+    /// Directory of the name cache (default: $XDG_CACHE_HOME/genpi). An empty string disables it
+    // PathBuf's default parser rejects an empty string
+    #[arg(long, value_name = "DIR", value_parser = clap::builder::TypedValueParser::map(clap::builder::OsStringValueParser::new(), PathBuf::from))]
+    cache_dir: Option<PathBuf>,
+    /// Seconds until the name cache expires
+    #[arg(long, value_name = "SECONDS", default_value_t = NamesCacheOptions::DEFAULT_TTL.as_secs())]
+    cache_ttl: u64,
+    /// Use only names all characters of which are in the charset
+    /// (sjis|cp932|euc-jp|jisx0208|jisx0208-level1, default: that of --encoding)
+    #[arg(long)]
+    charset: Option<Charset>,
+    /// Print N records (a JSON array with --format json)
+    #[arg(long, value_name = "N")]
+    count: Option<usize>,
+    /// Format of date_of_birth, e.g. [year]/[month]/[day] or %Y年%-m月%-d日
+    #[arg(long, value_name = "FORMAT")]
+    date_format: Option<DateFormat>,
+    /// Add the day of the week of date_of_birth (e.g. 月曜日)
+    #[arg(long)]
+    day_of_week: bool,
+    /// Replace names with edge cases (kanji outside JIS X 0208, very short or long names,
+    /// long readings and the small kana ゎ)
+    #[arg(long)]
+    edge_cases: bool,
+    /// Add an email address derived from the name (unique within the output)
+    #[arg(long)]
+    email: bool,
+    /// Comma separated domains for --email
+    #[arg(long, default_value = "example.com,example.jp")]
+    email_domains: EmailDomains,
+    /// Character encoding of the output (utf-8|sjis|cp932|euc-jp). JSON is always in utf-8
+    #[arg(long, default_value = "utf-8")]
+    encoding: Encoding,
+    /// Probability of female in 0.0..=1.0 (default: 0.5)
+    #[arg(long, conflicts_with = "sex")]
+    female_ratio: Option<FemaleRatio>,
+    /// Fields to output in order, optionally renamed (e.g. first_name:given_name,date_of_birth).
+    /// Selecting a field turns it on, e.g. age implies --age and phone_number --phone any
+    #[arg(long)]
+    fields: Option<Fields>,
+    /// Output format (json|ndjson|csv|tsv|sql)
+    #[arg(long, default_value = "json")]
+    format: OutputFormat,
     /// Print kana in katakana
     #[arg(long)]
     katakana: bool,
     /// Print katakana in half-width
     #[arg(long)]
     halfwidth: bool,
+    /// Generate people aged at most the age
+    #[arg(long)]
+    max_age: Option<u16>,
+    /// Generate people aged at least the age
+    #[arg(long)]
+    min_age: Option<u16>,
+    /// Conditions names must meet, e.g. last_name:contains:田,first_name_kana:starts_with:か.
+    /// Fields are last_name|last_name_kana|first_name|first_name_kana
+    /// and operators are contains|starts_with|length|charset
+    #[arg(long, value_name = "FILTERS")]
+    name_filter: Option<NameFilter>,
+    /// Sources of names in the order to fall back (namegen|cache|dictionary|file).
+    /// Use NAME_SOURCE with --server
+    #[arg(
+        long,
+        value_name = "SOURCES",
+        default_value = "namegen,cache,dictionary"
+    )]
+    name_source: NameSourceKinds,
+    /// A CSV or JSON file of names (last_name, last_name_kana, first_name, first_name_kana, sex)
+    /// tried before the other sources. Use NAMES_FILE with --server
+    #[arg(long, value_name = "PATH")]
+    names_file: Option<PathBuf>,
+    /// Unicode normalization of names (nfc|nfkc)
+    #[arg(long)]
+    normalization: Option<Normalization>,
+    /// Never access the network. Use the name cache even if it has expired
+    #[arg(long, conflicts_with = "refresh_cache")]
+    offline: bool,
+    /// Add a phone number (any|mobile|landline|ip)
+    #[arg(long, value_name = "TYPE")]
+    phone: Option<PhoneNumberType>,
+    /// Format of the phone number (hyphenated|plain|e164)
+    #[arg(long, default_value = "hyphenated")]
+    phone_format: PhoneNumberFormat,
+    /// HTTP proxy for namegen.jp (default: HTTPS_PROXY etc. in the environment)
+    #[arg(long, value_name = "URL")]
+    proxy: Option<String>,
+    /// Rarity of names (any|common|rare). Use NAME_RARITY with --server
+    #[arg(long, default_value = "any")]
+    rarity: Rarity,
+    /// Scrape names again ignoring the name cache
+    #[arg(long)]
+    refresh_cache: bool,
+    /// Times to retry a failed request to namegen.jp
+    #[arg(long, value_name = "N", default_value_t = NamegenClientOptions::DEFAULT_RETRIES)]
+    retries: u32,
+    /// Add romanized names (hepburn|passport|kunrei)
+    #[arg(long, value_name = "ROMANIZATION")]
+    romaji: Option<Romanization>,
+    /// Seed for the random number generator (same seed, same output)
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Fix the sex (female|male)
+    #[arg(long)]
+    sex: Option<Sex>,
     /// Start HTTP server
     #[arg(long)]
     server: bool,
+    /// Add the name source which generated each name
+    #[arg(long)]
+    source: bool,
+    /// Column names for --format sql (e.g. first_name:given_name,last_name:family_name)
+    #[arg(long, default_value = "")]
+    sql_columns: ColumnNames,
+    /// SQL dialect for --format sql (postgresql|mysql|sqlite)
+    #[arg(long, default_value = "postgresql")]
+    sql_dialect: SqlDialect,
+    /// Number of rows per INSERT statement for --format sql
+    #[arg(long, default_value_t = 100)]
+    sql_rows_per_statement: usize,
+    /// Add the star sign of date_of_birth (e.g. 牡羊座)
+    #[arg(long)]
+    star_sign: bool,
+    /// Table name for --format sql
+    #[arg(long, default_value = "pi")]
+    table: String,
+    /// Seconds until a request to namegen.jp times out
+    #[arg(long, value_name = "SECONDS", default_value_t = NamegenClientOptions::DEFAULT_TIMEOUT.as_secs(), value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: u64,
+    /// Render records from a template file (.json or .toml) instead of the fixed fields
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["katakana", "halfwidth"])]
+    template: Option<PathBuf>,
+    /// User-Agent header sent to namegen.jp
+    #[arg(long, default_value = NamegenClientOptions::DEFAULT_USER_AGENT)]
+    user_agent: String,
+    /// Add date_of_birth in the Japanese calendar (kanji|abbreviated)
+    #[arg(long, value_name = "FORMAT", num_args = 0..=1, default_missing_value = "kanji")]
+    wareki: Option<WarekiFormat>,
+    /// Add the zodiac sign (十二支) of the year of birth (e.g. 子)
+    #[arg(long)]
+    zodiac: bool,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt()
         .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
         .init();
 
     let cli = <Cli as clap::Parser>::parse();
+    if !cli.format.allows(cli.encoding) {
+        <Cli as clap::CommandFactory>::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--encoding other than utf-8 requires --format csv, tsv or sql",
+            )
+            .exit();
+    }
     if cli.server {
         Ok(run_server().await?)
     } else {
@@ -40,9 +217,117 @@ async fn main() -> anyhow::Result<()> {
             (true, false) => KanaForm::Katakana,
             (true, true) => KanaForm::HalfwidthKana,
         };
-        let generator = NamesCache::default();
-        let pi = generator.generate_pi(kana_form).await?;
-        println!("{}", serde_json::to_string(&pi)?);
+        let cache = NamesCacheOptions {
+            disk_cache: match cli.cache_dir {
+                Some(dir) if dir.as_os_str().is_empty() => None,
+                Some(dir) => Some(DiskCache::new(dir)),
+                None => DiskCache::default_dir().map(DiskCache::new),
+            },
+            mode: match (cli.offline, cli.refresh_cache) {
+                (true, _) => CacheMode::Offline,
+                (false, true) => CacheMode::Refresh,
+                (false, false) => CacheMode::Normal,
+            },
+            pool_size: NamesCacheOptions::DEFAULT_POOL_SIZE,
+            ttl: Duration::from_secs(cli.cache_ttl),
+        };
+        let client = NamegenClientOptions {
+            backoff: NamegenClientOptions::DEFAULT_BACKOFF,
+            proxy: cli.proxy,
+            retries: cli.retries,
+            timeout: Duration::from_secs(cli.timeout),
+            user_agent: cli.user_agent,
+        };
+        let generator = NameSource::new(NameSourceOptions {
+            kinds: cli.name_source,
+            names_file: cli.names_file,
+            rarity: cli.rarity,
+            client,
+            cache,
+        })?;
+        let mut options = GeneratePiOptions {
+            address: cli.address,
+            age: cli.age,
+            age_distribution: cli.age_distribution,
+            as_of: cli.as_of,
+            // names must be encodable in the output encoding
+            charset: cli.charset.or(cli.encoding.charset()),
+            date_of_birth: DateOfBirthConstraints {
+                min_age: cli.min_age,
+                max_age: cli.max_age,
+                born_after: cli.born_after,
+                born_before: cli.born_before,
+            },
+            day_of_week: cli.day_of_week,
+            edge_cases: cli.edge_cases,
+            email: cli.email,
+            email_domains: cli.email_domains,
+            female_ratio: cli.female_ratio,
+            kana_form,
+            name_filter: cli.name_filter.unwrap_or_default(),
+            normalization: cli.normalization,
+            phone_number: cli.phone,
+            phone_number_format: cli.phone_format,
+            romanization: cli.romaji,
+            seed: cli.seed,
+            sex: cli.sex,
+            source: cli.source,
+            star_sign: cli.star_sign,
+            wareki: cli.wareki,
+            zodiac: cli.zodiac,
+        };
+        let template = cli.template.as_deref().map(Template::read).transpose()?;
+        if let Some(fields) = &cli.fields {
+            match &template {
+                Some(template) => fields.check(&template.keys())?,
+                None => {
+                    fields.check(PI::FIELDS)?;
+                    // selecting a field turns it on
+                    options.enable_fields(fields.names());
+                }
+            }
+        }
+        let output_options = OutputOptions {
+            date_format: cli.date_format,
+            fields: cli.fields,
+            format: cli.format,
+            sql: SqlOptions {
+                column_names: cli.sql_columns,
+                dialect: cli.sql_dialect,
+                rows_per_statement: cli.sql_rows_per_statement,
+                table: cli.table,
+            },
+        };
+        let output = match (cli.count, &template) {
+            (None, None) => {
+                let pi = generator.generate_pi(options).await?;
+                output::render_one(&output_options, &pi)?
+            }
+            (None, Some(template)) => {
+                let pi = generator.generate_pi(options).await?;
+                output::render_one(&output_options, &template.render(&pi, 0)?)?
+            }
+            (Some(count), None) => {
+                let pis = generator.generate_pis(count, options).await?;
+                output::render(&output_options, &pis)?
+            }
+            (Some(count), Some(template)) => {
+                let pis = generator.generate_pis(count, options).await?;
+                let records = pis
+                    .iter()
+                    .enumerate()
+                    .map(|(index, pi)| template.render(pi, index))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                output::render(&output_options, &records)?
+            }
+        };
+        let output = match cli.format {
+            OutputFormat::Json => output + "\n",
+            OutputFormat::Ndjson | OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Sql => {
+                output
+            }
+        };
+        std::io::Write::write_all(&mut std::io::stdout(), &cli.encoding.encode(&output)?)?;
         Ok(())
     }
 }
@@ -1,9 +1,32 @@
+mod address;
+mod age_distribution;
+mod charset;
+mod date_format;
 mod date_of_birth;
+mod edge_case;
+mod email;
 mod name;
+mod name_filter;
+mod normalization;
+mod phone_number;
 mod pi;
+mod rarity;
+mod romaji;
 mod sex;
+mod wareki;
 
+pub use self::address::*;
+pub use self::age_distribution::*;
+pub use self::charset::*;
+pub use self::date_format::*;
 pub use self::date_of_birth::*;
+pub use self::email::*;
 pub use self::name::*;
+pub use self::name_filter::*;
+pub use self::normalization::*;
+pub use self::phone_number::*;
 pub use self::pi::*;
+pub use self::rarity::*;
+pub use self::romaji::*;
 pub use self::sex::*;
+pub use self::wareki::*;
@@ -0,0 +1,186 @@
+use std::sync::OnceLock;
+
+use rand::{
+    distributions::{Distribution, Standard},
+    seq::SliceRandom,
+    Rng,
+};
+
+use crate::model::Name;
+
+/// `postal code\tprefecture\tprefecture kana\tcity\tcity kana\ttown\ttown kana`
+const POSTAL_CODES: &str = include_str!("address/postal_codes.tsv");
+
+const BUILDING_NAMES: [&str; 12] = [
+    "グリーンハイツ",
+    "コーポ桜",
+    "サンライズ西町",
+    "シティハイム",
+    "パークレジデンス",
+    "ハイツ若葉",
+    "メゾン・ド・ソレイユ",
+    "リバーサイド東",
+    "レジデンス中央",
+    "ひまわり荘",
+    "第一ビル",
+    "藤ビル",
+];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Area {
+    postal_code: String,
+    prefecture: String,
+    prefecture_kana: String,
+    city: String,
+    city_kana: String,
+    town: String,
+    town_kana: String,
+}
+
+impl Area {
+    fn all() -> &'static [Self] {
+        static AREAS: OnceLock<Vec<Area>> = OnceLock::new();
+        AREAS.get_or_init(|| Self::parse().expect("embedded postal codes are valid"))
+    }
+
+    fn parse() -> anyhow::Result<Vec<Self>> {
+        POSTAL_CODES
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let columns = line.split('\t').collect::<Vec<&str>>();
+                anyhow::ensure!(columns.len() == 7, "invalid postal code entry: {line}");
+                // in_katakana and in_halfwidth_kana convert the kana
+                for kana in [columns[2], columns[4], columns[6]] {
+                    Name::hiragana_to_katakana(kana)?;
+                    Name::hiragana_to_halfwidth_kana(kana)?;
+                }
+                Ok(Self {
+                    postal_code: columns[0].to_owned(),
+                    prefecture: columns[1].to_owned(),
+                    prefecture_kana: columns[2].to_owned(),
+                    city: columns[3].to_owned(),
+                    city_kana: columns[4].to_owned(),
+                    town: columns[5].to_owned(),
+                    town_kana: columns[6].to_owned(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// A Japanese address. The area is real but the block number and the building are made up.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Address {
+    /// e.g. `100-0005`
+    pub postal_code: String,
+    pub prefecture: String,
+    pub prefecture_kana: String,
+    pub city: String,
+    pub city_kana: String,
+    pub town: String,
+    pub town_kana: String,
+    /// `chome-ban-go`, e.g. `1-2-3`
+    pub block_number: String,
+    /// Building and room, e.g. `コーポ桜 203号室`. `None` for a house
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub building: Option<String>,
+}
+
+impl Address {
+    pub fn in_katakana(self) -> Self {
+        Self {
+            prefecture_kana: Name::hiragana_to_katakana(&self.prefecture_kana)
+                .expect("postal_codes.tsv kana are hiragana"),
+            city_kana: Name::hiragana_to_katakana(&self.city_kana)
+                .expect("postal_codes.tsv kana are hiragana"),
+            town_kana: Name::hiragana_to_katakana(&self.town_kana)
+                .expect("postal_codes.tsv kana are hiragana"),
+            ..self
+        }
+    }
+
+    pub fn in_halfwidth_kana(self) -> Self {
+        Self {
+            prefecture_kana: Name::hiragana_to_halfwidth_kana(&self.prefecture_kana)
+                .expect("postal_codes.tsv kana are hiragana"),
+            city_kana: Name::hiragana_to_halfwidth_kana(&self.city_kana)
+                .expect("postal_codes.tsv kana are hiragana"),
+            town_kana: Name::hiragana_to_halfwidth_kana(&self.town_kana)
+                .expect("postal_codes.tsv kana are hiragana"),
+            ..self
+        }
+    }
+}
+
+impl Distribution<Address> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Address {
+        let area = Area::all().choose(rng).expect("postal codes are not empty");
+        let block_number = format!(
+            "{}-{}-{}",
+            rng.gen_range(1..=5),
+            rng.gen_range(1..=30),
+            rng.gen_range(1..=20)
+        );
+        let building = if rng.gen::<bool>() {
+            let name = BUILDING_NAMES.choose(rng).expect("not empty");
+            let room = rng.gen_range(1..=10) * 100 + rng.gen_range(1..=8);
+            Some(format!("{name} {room}号室"))
+        } else {
+            None
+        };
+        Address {
+            postal_code: area.postal_code.clone(),
+            prefecture: area.prefecture.clone(),
+            prefecture_kana: area.prefecture_kana.clone(),
+            city: area.city.clone(),
+            city_kana: area.city_kana.clone(),
+            town: area.town.clone(),
+            town_kana: area.town_kana.clone(),
+            block_number,
+            building,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        let areas = Area::parse()?;
+        assert!(!areas.is_empty());
+        for area in areas {
+            let (first, second) = area
+                .postal_code
+                .split_once('-')
+                .ok_or_else(|| anyhow::anyhow!("{}", area.postal_code))?;
+            assert!(first.len() == 3 && first.chars().all(|c| c.is_ascii_digit()));
+            assert!(second.len() == 4 && second.chars().all(|c| c.is_ascii_digit()));
+            for kana in [&area.prefecture_kana, &area.city_kana, &area.town_kana] {
+                Name::hiragana_to_katakana(kana)?;
+                Name::hiragana_to_halfwidth_kana(kana)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            let address = rng.gen::<Address>();
+            assert_eq!(address.block_number.split('-').count(), 3);
+            let address = address.in_halfwidth_kana();
+            assert!(!address.prefecture_kana.is_empty());
+        }
+        let address = rng.gen::<Address>().in_katakana();
+        assert!(address
+            .city_kana
+            .chars()
+            .all(|c| ('\u{30a1}'..='\u{30f6}').contains(&c)));
+    }
+}
@@ -0,0 +1,174 @@
+use std::{ops::RangeInclusive, str::FromStr};
+
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    Rng,
+};
+use time::Date;
+
+use crate::model::{date_of_birth::years_before, DateOfBirth, Sex};
+
+/// Japanese population by 5-year age group in thousands (2020 census).
+/// `(minimum age, female, male)`. The last group is 100 years old and over.
+const POPULATION: [(u16, u32, u32); 21] = [
+    (0, 2276, 2386),
+    (5, 2459, 2581),
+    (10, 2620, 2754),
+    (15, 2725, 2878),
+    (20, 2887, 3023),
+    (25, 2882, 3050),
+    (30, 3167, 3286),
+    (35, 3614, 3710),
+    (40, 4052, 4156),
+    (45, 4822, 4919),
+    (50, 4304, 4357),
+    (55, 3874, 3867),
+    (60, 3761, 3678),
+    (65, 4339, 4093),
+    (70, 4853, 4303),
+    (75, 3831, 3178),
+    (80, 3009, 2226),
+    (85, 2176, 1223),
+    (90, 1101, 405),
+    (95, 307, 70),
+    (100, 69, 11),
+];
+
+/// The maximum age of the last group
+const MAX_AGE: u16 = 119;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgeDistribution {
+    /// Every date in the range is equally likely
+    #[default]
+    Uniform,
+    /// Weighted by the population of Japan by age and sex
+    Population,
+}
+
+impl FromStr for AgeDistribution {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "uniform" => Ok(Self::Uniform),
+            "population" => Ok(Self::Population),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not a valid age distribution (uniform|population)"
+            )),
+        }
+    }
+}
+
+/// Samples a date of birth in the range weighted by the population pyramid.
+///
+/// Dates in the range that no age group covers (e.g. future dates) are never sampled
+/// unless no age group overlaps the range at all, in which case it falls back to uniform.
+pub struct PopulationDateOfBirth {
+    cohorts: Vec<RangeInclusive<DateOfBirth>>,
+    weights: Option<WeightedIndex<f64>>,
+    range: RangeInclusive<DateOfBirth>,
+}
+
+impl PopulationDateOfBirth {
+    pub fn new(sex: Sex, range: RangeInclusive<DateOfBirth>, today: Date) -> Self {
+        let (start, end) = (Date::from(*range.start()), Date::from(*range.end()));
+        let mut cohorts = vec![];
+        let mut weights = vec![];
+        for (i, (min_age, female, male)) in POPULATION.iter().enumerate() {
+            let max_age = POPULATION
+                .get(i + 1)
+                .map(|(next_min_age, _, _)| next_min_age - 1)
+                .unwrap_or(MAX_AGE);
+            let population = match sex {
+                Sex::Female => *female,
+                Sex::Male => *male,
+            };
+            let cohort_start = years_before(today, max_age + 1)
+                .next_day()
+                .expect("invalid date");
+            let cohort_end = years_before(today, *min_age);
+            let overlap_start = cohort_start.max(start);
+            let overlap_end = cohort_end.min(end);
+            if overlap_start > overlap_end {
+                continue;
+            }
+            let cohort_days = (cohort_end - cohort_start).whole_days() + 1;
+            let overlap_days = (overlap_end - overlap_start).whole_days() + 1;
+            cohorts.push(DateOfBirth::from(overlap_start)..=DateOfBirth::from(overlap_end));
+            weights.push(f64::from(population) * overlap_days as f64 / cohort_days as f64);
+        }
+        Self {
+            cohorts,
+            weights: WeightedIndex::new(weights).ok(),
+            range,
+        }
+    }
+}
+
+impl Distribution<DateOfBirth> for PopulationDateOfBirth {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> DateOfBirth {
+        match &self.weights {
+            Some(weights) => {
+                let cohort = self.cohorts[weights.sample(rng)].clone();
+                rng.gen_range(cohort)
+            }
+            None => rng.gen_range(self.range.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        assert_eq!(
+            "uniform".parse::<AgeDistribution>()?,
+            AgeDistribution::Uniform
+        );
+        assert_eq!(
+            "population".parse::<AgeDistribution>()?,
+            AgeDistribution::Population
+        );
+        assert!("normal".parse::<AgeDistribution>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sample() -> anyhow::Result<()> {
+        let today = date!(2024 - 06 - 15);
+        let range = "1904-01-01".parse::<DateOfBirth>()?..="2024-12-31".parse::<DateOfBirth>()?;
+        let distribution = PopulationDateOfBirth::new(Sex::Male, range.clone(), today);
+        let mut rng = StdRng::seed_from_u64(1);
+        let centenarian = DateOfBirth::from(years_before(today, 100));
+        let mut centenarians = 0;
+        for _ in 0..10_000 {
+            let dob = distribution.sample(&mut rng);
+            assert!(range.contains(&dob));
+            // future dates are never sampled
+            assert!(dob <= DateOfBirth::from(today));
+            if dob <= centenarian {
+                centenarians += 1;
+            }
+        }
+        // about 0.01% of men are 100 years old or over
+        assert!(centenarians < 10, "{centenarians}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_sample_fallback() -> anyhow::Result<()> {
+        let today = date!(2024 - 06 - 15);
+        let range = "2024-07-01".parse::<DateOfBirth>()?..="2024-07-01".parse::<DateOfBirth>()?;
+        let distribution = PopulationDateOfBirth::new(Sex::Female, range, today);
+        let dob = distribution.sample(&mut StdRng::seed_from_u64(1));
+        assert_eq!(dob, "2024-07-01".parse()?);
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use std::str::FromStr;
+
+/// A character set legacy systems accept
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Charset {
+    /// ASCII except `\` and `~` (¥ and ‾ in JIS X 0201), halfwidth katakana and JIS X 0208
+    ShiftJis,
+    /// Shift_JIS with the NEC and IBM extensions
+    Cp932,
+    /// ASCII, halfwidth katakana and JIS X 0208. JIS X 0212 is not supported
+    EucJp,
+    /// JIS X 0208 non-kanji and level 1 and 2 kanji
+    JisX0208,
+    /// JIS X 0208 non-kanji and level 1 kanji
+    JisX0208Level1,
+}
+
+impl Charset {
+    pub fn contains(&self, c: char) -> bool {
+        match self {
+            Self::ShiftJis => {
+                (c.is_ascii() && !matches!(c, '\\' | '~'))
+                    || is_halfwidth_katakana(c)
+                    || jis_x_0208_row(c).is_some()
+            }
+            Self::EucJp => c.is_ascii() || is_halfwidth_katakana(c) || jis_x_0208_row(c).is_some(),
+            Self::Cp932 => {
+                let mut buf = [0; 4];
+                let (_, _, has_errors) = encoding_rs::SHIFT_JIS.encode(c.encode_utf8(&mut buf));
+                !has_errors
+            }
+            Self::JisX0208 => jis_x_0208_row(c).is_some(),
+            // rows 16 to 47 are level 1 kanji
+            Self::JisX0208Level1 => jis_x_0208_row(c).is_some_and(|row| row < 48),
+        }
+    }
+
+    /// The first character not in the charset
+    pub fn find_unrepresentable(&self, s: &str) -> Option<char> {
+        s.chars().find(|c| !self.contains(*c))
+    }
+}
+
+fn is_halfwidth_katakana(c: char) -> bool {
+    ('\u{ff61}'..='\u{ff9f}').contains(&c)
+}
+
+/// The row (ku) of a JIS X 0208 character. Rows 1 to 8 are non-kanji and 16 to 84 are kanji.
+fn jis_x_0208_row(c: char) -> Option<u8> {
+    let mut buf = [0; 4];
+    let (bytes, _, has_errors) = encoding_rs::SHIFT_JIS.encode(c.encode_utf8(&mut buf));
+    let (lead, trail) = match (has_errors, bytes.as_ref()) {
+        (false, [lead, trail]) => (*lead, *trail),
+        _ => return None,
+    };
+    let row = match lead {
+        0x81..=0x9f => (lead - 0x81) * 2 + 1,
+        0xe0..=0xef => (lead - 0xc1) * 2 + 1,
+        _ => return None,
+    } + u8::from(trail >= 0x9f);
+    // row 13 and rows from 85 are NEC and IBM extensions
+    matches!(row, 1..=8 | 16..=84).then_some(row)
+}
+
+impl FromStr for Charset {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sjis" => Ok(Self::ShiftJis),
+            "cp932" => Ok(Self::Cp932),
+            "euc-jp" => Ok(Self::EucJp),
+            "jisx0208" => Ok(Self::JisX0208),
+            "jisx0208-level1" => Ok(Self::JisX0208Level1),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not a valid charset (sjis|cp932|euc-jp|jisx0208|jisx0208-level1)"
+            )),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Charset {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<Self>().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        assert_eq!("sjis".parse::<Charset>()?, Charset::ShiftJis);
+        assert_eq!(
+            "jisx0208-level1".parse::<Charset>()?,
+            Charset::JisX0208Level1
+        );
+        assert_eq!(
+            "utf-8".parse::<Charset>().unwrap_err().to_string(),
+            "utf-8 is not a valid charset (sjis|cp932|euc-jp|jisx0208|jisx0208-level1)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_contains() {
+        use Charset::*;
+        // 亜: level 1, 弌: level 2, 髙 and 﨑: IBM extensions, ①: NEC special, 𠮷: JIS X 0213
+        for (c, expected) in [
+            ('A', [true, true, true, false, false]),
+            ('\\', [false, true, true, false, false]),
+            ('~', [false, true, true, false, false]),
+            ('ｱ', [true, true, true, false, false]),
+            ('あ', [true, true, true, true, true]),
+            ('亜', [true, true, true, true, true]),
+            ('弌', [true, true, true, true, false]),
+            ('髙', [false, true, false, false, false]),
+            ('﨑', [false, true, false, false, false]),
+            ('①', [false, true, false, false, false]),
+            ('𠮷', [false, false, false, false, false]),
+            ('ゔ', [false, false, false, false, false]),
+        ] {
+            let actual = [ShiftJis, Cp932, EucJp, JisX0208, JisX0208Level1]
+                .map(|charset| charset.contains(c));
+            assert_eq!(actual, expected, "{c}");
+        }
+        assert_eq!(JisX0208.find_unrepresentable("髙橋"), Some('髙'));
+        assert_eq!(JisX0208.find_unrepresentable("高橋"), None);
+    }
+}
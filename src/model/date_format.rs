@@ -0,0 +1,97 @@
+use std::str::FromStr;
+
+use anyhow::Context;
+use time::{format_description::OwnedFormatItem, Date};
+
+/// A date format. Either a `time` format description (`[year]/[month]/[day]`)
+/// or a strftime-like format (`%Y年%-m月%-d日`).
+///
+/// strftime-like formats support `%Y`, `%y`, `%m`, `%-m`, `%d`, `%-d` and `%%`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DateFormat(OwnedFormatItem);
+
+impl FromStr for DateFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let description = if s.contains('%') {
+            Self::strftime_to_description(s)?
+        } else {
+            s.to_owned()
+        };
+        let item = time::format_description::parse_owned::<1>(&description)
+            .with_context(|| format!("{s} is not a valid date format"))?;
+        Ok(Self(item))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for DateFormat {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl DateFormat {
+    pub fn format(&self, date: Date) -> anyhow::Result<String> {
+        Ok(date.format(&self.0)?)
+    }
+
+    fn strftime_to_description(s: &str) -> anyhow::Result<String> {
+        let mut description = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '%' => {
+                    let specifier = match chars.next() {
+                        Some('-') => format!("-{}", chars.next().unwrap_or_default()),
+                        Some(c) => c.to_string(),
+                        None => String::new(),
+                    };
+                    description.push_str(match specifier.as_str() {
+                        "Y" => "[year]",
+                        "y" => "[year repr:last_two]",
+                        "m" => "[month]",
+                        "-m" => "[month padding:none]",
+                        "d" => "[day]",
+                        "-d" => "[day padding:none]",
+                        "%" => "%",
+                        _ => anyhow::bail!("%{specifier} is not supported in {s}"),
+                    });
+                }
+                '[' => description.push_str("[["),
+                c => description.push(c),
+            }
+        }
+        Ok(description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn test_format() -> anyhow::Result<()> {
+        let table = [
+            ("[year]/[month]/[day]", "2001/02/03"),
+            ("%Y/%m/%d", "2001/02/03"),
+            ("%Y年%-m月%-d日", "2001年2月3日"),
+            ("%y%m%d [%%]", "010203 [%]"),
+        ];
+        for (format, expected) in table {
+            assert_eq!(
+                format
+                    .parse::<DateFormat>()?
+                    .format(date!(2001 - 02 - 03))?,
+                expected,
+                "{format}"
+            );
+        }
+        assert!("%Q".parse::<DateFormat>().is_err());
+        assert!("[yaer]".parse::<DateFormat>().is_err());
+        Ok(())
+    }
+}
@@ -1,7 +1,10 @@
-use std::{ops::Range, str::FromStr};
+use std::{
+    ops::{Range, RangeInclusive},
+    str::FromStr,
+};
 
 use rand::Rng;
-use time::{macros::format_description, Date, Duration};
+use time::{macros::format_description, Date, Duration, Month, Weekday};
 
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct DateOfBirth(Date);
@@ -12,6 +15,12 @@ impl From<Date> for DateOfBirth {
     }
 }
 
+impl From<DateOfBirth> for Date {
+    fn from(date_of_birth: DateOfBirth) -> Self {
+        date_of_birth.0
+    }
+}
+
 impl FromStr for DateOfBirth {
     type Err = anyhow::Error;
 
@@ -33,6 +42,146 @@ impl serde::Serialize for DateOfBirth {
     }
 }
 
+impl<'de> serde::Deserialize<'de> for DateOfBirth {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// 十二支 from 子 (e.g. 2020)
+const ZODIAC: [&str; 12] = [
+    "子", "丑", "寅", "卯", "辰", "巳", "午", "未", "申", "酉", "戌", "亥",
+];
+
+/// `(month, first day, sign)` in the order of the calendar
+const STAR_SIGNS: [(u8, u8, &str); 12] = [
+    (1, 20, "水瓶座"),
+    (2, 19, "魚座"),
+    (3, 21, "牡羊座"),
+    (4, 20, "牡牛座"),
+    (5, 21, "双子座"),
+    (6, 22, "蟹座"),
+    (7, 23, "獅子座"),
+    (8, 23, "乙女座"),
+    (9, 23, "天秤座"),
+    (10, 24, "蠍座"),
+    (11, 23, "射手座"),
+    (12, 22, "山羊座"),
+];
+
+impl DateOfBirth {
+    /// Age on `as_of`. 0 if not born yet
+    pub fn age(&self, as_of: Date) -> u16 {
+        let mut age = as_of.year() - self.0.year();
+        if (u8::from(as_of.month()), as_of.day()) < (u8::from(self.0.month()), self.0.day()) {
+            age -= 1;
+        }
+        u16::try_from(age).unwrap_or_default()
+    }
+
+    /// e.g. `月曜日`
+    pub fn day_of_week(&self) -> &'static str {
+        match self.0.weekday() {
+            Weekday::Monday => "月曜日",
+            Weekday::Tuesday => "火曜日",
+            Weekday::Wednesday => "水曜日",
+            Weekday::Thursday => "木曜日",
+            Weekday::Friday => "金曜日",
+            Weekday::Saturday => "土曜日",
+            Weekday::Sunday => "日曜日",
+        }
+    }
+
+    /// 十二支 of the year, e.g. `子`
+    pub fn zodiac(&self) -> &'static str {
+        ZODIAC[(self.0.year() - 4).rem_euclid(12) as usize]
+    }
+
+    /// 星座, e.g. `牡羊座`
+    pub fn star_sign(&self) -> &'static str {
+        let month_day = (u8::from(self.0.month()), self.0.day());
+        STAR_SIGNS
+            .iter()
+            .rev()
+            .find(|(month, day, _)| (*month, *day) <= month_day)
+            .map(|(_, _, sign)| *sign)
+            // January 1st to 19th
+            .unwrap_or("山羊座")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum DateOfBirthRangeError {
+    #[error("min_age must be less than or equal to max_age")]
+    InvertedAge,
+    #[error("date of birth range is empty")]
+    Empty,
+}
+
+/// Constraints on date of birth. All of them are optional.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DateOfBirthConstraints {
+    pub min_age: Option<u16>,
+    pub max_age: Option<u16>,
+    /// Exclusive lower bound
+    pub born_after: Option<DateOfBirth>,
+    /// Exclusive upper bound
+    pub born_before: Option<DateOfBirth>,
+}
+
+impl DateOfBirthConstraints {
+    /// Returns the inclusive range of date of birth as of `today`.
+    ///
+    /// Without constraints, the range is from January 1st 120 years ago to December 31st of this year.
+    pub fn range(&self, today: Date) -> Result<RangeInclusive<DateOfBirth>, DateOfBirthRangeError> {
+        if let (Some(min_age), Some(max_age)) = (self.min_age, self.max_age) {
+            if min_age > max_age {
+                return Err(DateOfBirthRangeError::InvertedAge);
+            }
+        }
+
+        let mut start =
+            Date::from_calendar_date(today.year() - 120, Month::January, 1).expect("invalid date");
+        let mut end =
+            Date::from_calendar_date(today.year(), Month::December, 31).expect("invalid date");
+        if self.min_age.is_some() || self.max_age.is_some() {
+            // who are min_age years old today were born on or before this date
+            end = end.min(years_before(today, self.min_age.unwrap_or_default()));
+        }
+        if let Some(max_age) = self.max_age {
+            // who are max_age + 1 years old today were born on or before this date
+            start = start.max(
+                years_before(today, max_age.saturating_add(1))
+                    .next_day()
+                    .expect("invalid date"),
+            );
+        }
+        if let Some(DateOfBirth(born_after)) = self.born_after {
+            start = start.max(born_after.next_day().ok_or(DateOfBirthRangeError::Empty)?);
+        }
+        if let Some(DateOfBirth(born_before)) = self.born_before {
+            end = end.min(
+                born_before
+                    .previous_day()
+                    .ok_or(DateOfBirthRangeError::Empty)?,
+            );
+        }
+        if start > end {
+            return Err(DateOfBirthRangeError::Empty);
+        }
+        Ok(DateOfBirth(start)..=DateOfBirth(end))
+    }
+}
+
+pub(super) fn years_before(date: Date, years: u16) -> Date {
+    let year = date.year() - i32::from(years);
+    date.replace_year(year)
+        // February 29th in a non-leap year
+        .or_else(|_| Date::from_calendar_date(year, date.month(), date.day() - 1))
+        .unwrap_or(Date::MIN)
+}
+
 pub struct UniformDateOfBirth(Range<Date>);
 
 impl rand::distributions::uniform::UniformSampler for UniformDateOfBirth {
@@ -85,6 +234,31 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_derived() -> anyhow::Result<()> {
+        let dob = "2000-02-29".parse::<DateOfBirth>()?;
+        assert_eq!(
+            dob.age(Date::from_calendar_date(2001, Month::February, 28)?),
+            0
+        );
+        assert_eq!(dob.age(Date::from_calendar_date(2001, Month::March, 1)?), 1);
+        assert_eq!(
+            dob.age(Date::from_calendar_date(2024, Month::February, 29)?),
+            24
+        );
+        assert_eq!(
+            dob.age(Date::from_calendar_date(1999, Month::January, 1)?),
+            0
+        );
+        assert_eq!(dob.day_of_week(), "火曜日");
+        assert_eq!(dob.zodiac(), "辰");
+        assert_eq!(dob.star_sign(), "魚座");
+        assert_eq!("2020-01-19".parse::<DateOfBirth>()?.star_sign(), "山羊座");
+        assert_eq!("2020-01-20".parse::<DateOfBirth>()?.star_sign(), "水瓶座");
+        assert_eq!("2020-12-31".parse::<DateOfBirth>()?.zodiac(), "子");
+        Ok(())
+    }
+
     #[test]
     fn test_from_date() -> anyhow::Result<()> {
         let date = Date::parse("2020-01-02", format_description!("[year]-[month]-[day]"))?;
@@ -102,6 +276,95 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_deserialize() -> anyhow::Result<()> {
+        let dob: DateOfBirth = serde_json::from_str(r#""2020-01-02""#)?;
+        assert_eq!(dob, "2020-01-02".parse()?);
+        assert!(serde_json::from_str::<DateOfBirth>(r#""2020/01/02""#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_constraints_range() -> anyhow::Result<()> {
+        let today = Date::parse("2024-02-29", format_description!("[year]-[month]-[day]"))?;
+        let dob = |s: &str| s.parse::<DateOfBirth>();
+
+        assert_eq!(
+            DateOfBirthConstraints::default().range(today)?,
+            dob("1904-01-01")?..=dob("2024-12-31")?
+        );
+        assert_eq!(
+            DateOfBirthConstraints {
+                min_age: Some(20),
+                max_age: Some(29),
+                ..Default::default()
+            }
+            .range(today)?,
+            dob("1994-03-01")?..=dob("2004-02-29")?
+        );
+        assert_eq!(
+            DateOfBirthConstraints {
+                min_age: Some(1),
+                ..Default::default()
+            }
+            .range(today)?,
+            dob("1904-01-01")?..=dob("2023-02-28")?
+        );
+        assert_eq!(
+            DateOfBirthConstraints {
+                max_age: Some(0),
+                ..Default::default()
+            }
+            .range(today)?,
+            dob("2023-03-01")?..=dob("2024-02-29")?
+        );
+        assert_eq!(
+            DateOfBirthConstraints {
+                born_after: Some(dob("1980-01-01")?),
+                born_before: Some(dob("1990-01-01")?),
+                ..Default::default()
+            }
+            .range(today)?,
+            dob("1980-01-02")?..=dob("1989-12-31")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_constraints_range_error() -> anyhow::Result<()> {
+        let today = Date::parse("2024-02-29", format_description!("[year]-[month]-[day]"))?;
+        let dob = |s: &str| s.parse::<DateOfBirth>();
+
+        assert_eq!(
+            DateOfBirthConstraints {
+                min_age: Some(30),
+                max_age: Some(20),
+                ..Default::default()
+            }
+            .range(today),
+            Err(DateOfBirthRangeError::InvertedAge)
+        );
+        assert_eq!(
+            DateOfBirthConstraints {
+                born_after: Some(dob("1990-01-01")?),
+                born_before: Some(dob("1990-01-02")?),
+                ..Default::default()
+            }
+            .range(today),
+            Err(DateOfBirthRangeError::Empty)
+        );
+        assert_eq!(
+            DateOfBirthConstraints {
+                min_age: Some(20),
+                born_after: Some(dob("2010-01-01")?),
+                ..Default::default()
+            }
+            .range(today),
+            Err(DateOfBirthRangeError::Empty)
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_gen_range() -> anyhow::Result<()> {
         let mut rng = rand::thread_rng();
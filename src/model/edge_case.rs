@@ -0,0 +1,196 @@
+use std::sync::OnceLock;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::model::{Name, NameFilter, Sex};
+
+/// `last|female|male\tname\tkana`
+///
+/// Names with kanji outside JIS X 0208 (髙, 𠮷, 﨑, ...), one or many characters,
+/// the iteration mark 々, readings of 12 kana or more and the small kana ゎ
+/// in the historical spelling of kanji read くゎ (e.g. 桑原 くゎばら)
+const EDGE_CASE_NAMES: &str = include_str!("edge_case/names.tsv");
+
+#[derive(Debug, Default)]
+struct EdgeCaseNames {
+    last_names: Vec<(String, String)>,
+    female_first_names: Vec<(String, String)>,
+    male_first_names: Vec<(String, String)>,
+}
+
+impl EdgeCaseNames {
+    fn get() -> &'static Self {
+        static NAMES: OnceLock<EdgeCaseNames> = OnceLock::new();
+        NAMES.get_or_init(|| Self::parse().expect("embedded edge case names are valid"))
+    }
+
+    fn parse() -> anyhow::Result<Self> {
+        let mut names = Self::default();
+        for line in EDGE_CASE_NAMES.lines().filter(|line| !line.is_empty()) {
+            let columns = line.split('\t').collect::<Vec<&str>>();
+            anyhow::ensure!(columns.len() == 3, "invalid edge case name entry: {line}");
+            let entry = (columns[1].to_owned(), columns[2].to_owned());
+            match columns[0] {
+                "last" => names.last_names.push(entry),
+                "female" => names.female_first_names.push(entry),
+                "male" => names.male_first_names.push(entry),
+                _ => anyhow::bail!("invalid edge case name entry: {line}"),
+            }
+        }
+        Ok(names)
+    }
+}
+
+impl Name {
+    /// Replaces the last name, the first name or both with edge cases matching the filter.
+    /// Returns `None` if no edge case matches
+    pub fn with_edge_cases<R: Rng + ?Sized>(
+        self,
+        sex: Sex,
+        filter: &NameFilter,
+        rng: &mut R,
+    ) -> Option<Self> {
+        let names = EdgeCaseNames::get();
+        let first_names = match sex {
+            Sex::Female => &names.female_first_names,
+            Sex::Male => &names.male_first_names,
+        };
+        let last_names = names
+            .last_names
+            .iter()
+            .filter(|(last_name, kana)| filter.matches_last_name(last_name, kana))
+            .collect::<Vec<&(String, String)>>();
+        let first_names = first_names
+            .iter()
+            .filter(|(first_name, kana)| filter.matches_first_name(first_name, kana))
+            .collect::<Vec<&(String, String)>>();
+        let keeps_last_name = filter.matches_last_name(&self.last_name, &self.last_name_kana);
+        let keeps_first_name = filter.matches_first_name(&self.first_name, &self.first_name_kana);
+        // 0: the last name, 1: the first name, 2: both
+        let mut replacements = [0, 1, 2];
+        replacements.shuffle(rng);
+        let replaced = replacements.into_iter().find(|replaced| match replaced {
+            0 => !last_names.is_empty() && keeps_first_name,
+            1 => keeps_last_name && !first_names.is_empty(),
+            _ => !last_names.is_empty() && !first_names.is_empty(),
+        })?;
+        let (last_name, last_name_kana) = match replaced {
+            1 => (self.last_name, self.last_name_kana),
+            _ => (*last_names.choose(rng).expect("last_names is not empty")).clone(),
+        };
+        let (first_name, first_name_kana) = match replaced {
+            0 => (self.first_name, self.first_name_kana),
+            _ => (*first_names.choose(rng).expect("first_names is not empty")).clone(),
+        };
+        Some(Self {
+            first_name,
+            first_name_kana,
+            last_name,
+            last_name_kana,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::model::{Charset, Romanization};
+
+    use super::*;
+
+    fn is_edge_case(name: &str, kana: &str) -> bool {
+        let length = name.chars().count();
+        Charset::JisX0208.find_unrepresentable(name).is_some()
+            || length == 1
+            || length >= 4
+            || name.contains('々')
+            || kana.chars().count() >= 12
+            || kana.contains('ゎ')
+    }
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        let names = EdgeCaseNames::parse()?;
+        assert!(!names.last_names.is_empty());
+        assert!(!names.female_first_names.is_empty());
+        assert!(!names.male_first_names.is_empty());
+        for (name, kana) in names
+            .last_names
+            .iter()
+            .chain(names.female_first_names.iter())
+            .chain(names.male_first_names.iter())
+        {
+            assert!(
+                is_edge_case(name, kana),
+                "{name} {kana} is not an edge case"
+            );
+            Romanization::Hepburn.romanize(kana)?;
+        }
+        assert!(EDGE_CASE_NAMES.contains('ゎ'));
+        assert!(EDGE_CASE_NAMES.lines().any(|line| line
+            .split('\t')
+            .nth(2)
+            .is_some_and(|kana| kana.chars().count() >= 12)));
+        for (last_name, last_name_kana) in &names.last_names {
+            for (first_name, first_name_kana) in names
+                .female_first_names
+                .iter()
+                .chain(names.male_first_names.iter())
+            {
+                Name {
+                    first_name: first_name.clone(),
+                    first_name_kana: first_name_kana.clone(),
+                    last_name: last_name.clone(),
+                    last_name_kana: last_name_kana.clone(),
+                }
+                .validate()?;
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_edge_cases() -> anyhow::Result<()> {
+        let names = EdgeCaseNames::get();
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let name = Name {
+                first_name: "太郎".to_owned(),
+                first_name_kana: "たろう".to_owned(),
+                last_name: "山田".to_owned(),
+                last_name_kana: "やまだ".to_owned(),
+            }
+            .with_edge_cases(Sex::Male, &NameFilter::default(), &mut rng)
+            .expect("every edge case matches");
+            let is_edge_last_name = names
+                .last_names
+                .iter()
+                .any(|(last_name, _)| *last_name == name.last_name);
+            let is_edge_first_name = names
+                .male_first_names
+                .iter()
+                .any(|(first_name, _)| *first_name == name.first_name);
+            assert!(is_edge_last_name || is_edge_first_name, "{name:?}");
+        }
+
+        let name = Name {
+            first_name: "太郎".to_owned(),
+            first_name_kana: "たろう".to_owned(),
+            last_name: "山田".to_owned(),
+            last_name_kana: "やまだ".to_owned(),
+        };
+        // only 𠮷田 matches
+        let filter = "last_name_kana:starts_with:よし".parse::<NameFilter>()?;
+        for _ in 0..10 {
+            let edge_case = name
+                .clone()
+                .with_edge_cases(Sex::Male, &filter, &mut rng)
+                .expect("𠮷田 matches");
+            assert_eq!(edge_case.last_name, "𠮷田");
+        }
+        let filter = "last_name:length:10".parse::<NameFilter>()?;
+        assert!(name.with_edge_cases(Sex::Male, &filter, &mut rng).is_none());
+        Ok(())
+    }
+}
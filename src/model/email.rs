@@ -0,0 +1,219 @@
+use std::{collections::HashSet, str::FromStr};
+
+use rand::{seq::SliceRandom, Rng};
+use time::Date;
+
+use crate::model::{DateOfBirth, Name, Romanization};
+
+/// Domains for email addresses, e.g. `example.com,example.jp`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmailDomains(Vec<String>);
+
+impl Default for EmailDomains {
+    fn default() -> Self {
+        // reserved for documentation (RFC 2606 and JPRS)
+        Self(vec!["example.com".to_owned(), "example.jp".to_owned()])
+    }
+}
+
+impl FromStr for EmailDomains {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let domains = s
+            .split(',')
+            .map(|domain| {
+                if domain.is_empty()
+                    || !domain
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+                {
+                    Err(anyhow::anyhow!("{domain} is not a valid email domain"))
+                } else {
+                    Ok(domain.to_ascii_lowercase())
+                }
+            })
+            .collect::<anyhow::Result<Vec<String>>>()?;
+        Ok(Self(domains))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for EmailDomains {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Pattern {
+    /// `taro.yamada`
+    FirstDotLast,
+    /// `t.yamada`
+    InitialDotLast,
+    /// `yamada1985`
+    LastYear,
+    /// `yamada_taro`
+    LastUnderscoreFirst,
+}
+
+const PATTERNS: [Pattern; 4] = [
+    Pattern::FirstDotLast,
+    Pattern::InitialDotLast,
+    Pattern::LastYear,
+    Pattern::LastUnderscoreFirst,
+];
+
+/// Generates email addresses that are unique within the generator
+#[derive(Debug)]
+pub struct EmailGenerator {
+    domains: EmailDomains,
+    used: HashSet<String>,
+}
+
+impl EmailGenerator {
+    pub fn new(domains: EmailDomains) -> Self {
+        Self {
+            domains,
+            used: HashSet::new(),
+        }
+    }
+
+    /// `name` must be in hiragana
+    pub fn generate<R: Rng + ?Sized>(
+        &mut self,
+        name: &Name,
+        date_of_birth: DateOfBirth,
+        rng: &mut R,
+    ) -> anyhow::Result<String> {
+        let first = Self::local_part(&name.first_name_kana)?;
+        let last = Self::local_part(&name.last_name_kana)?;
+        // every pattern has the last name
+        anyhow::ensure!(
+            !last.is_empty(),
+            "last_name_kana {} has no letters for an email address",
+            name.last_name_kana
+        );
+        let pattern = if first.is_empty() {
+            &Pattern::LastYear
+        } else {
+            PATTERNS.choose(rng).expect("not empty")
+        };
+        let local_part = match pattern {
+            Pattern::FirstDotLast => format!("{first}.{last}"),
+            Pattern::InitialDotLast => {
+                let initial = first.chars().next().expect("first is not empty");
+                format!("{initial}.{last}")
+            }
+            Pattern::LastYear => format!("{last}{}", Date::from(date_of_birth).year()),
+            Pattern::LastUnderscoreFirst => format!("{last}_{first}"),
+        };
+        let domain = self.domains.0.choose(rng).expect("domains are not empty");
+        let mut email = format!("{local_part}@{domain}");
+        let mut suffix = 2;
+        while self.used.contains(&email) {
+            email = format!("{local_part}{suffix}@{domain}");
+            suffix += 1;
+        }
+        self.used.insert(email.clone());
+        Ok(email)
+    }
+
+    fn local_part(hiragana: &str) -> anyhow::Result<String> {
+        // passport romanization has no diacritics
+        Ok(Romanization::Passport
+            .romanize(hiragana)?
+            .to_ascii_lowercase()
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn name() -> Name {
+        Name {
+            first_name: "太郎".to_owned(),
+            first_name_kana: "たろう".to_owned(),
+            last_name: "大貫".to_owned(),
+            last_name_kana: "おおぬき".to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_email_domains_from_str() -> anyhow::Result<()> {
+        assert_eq!(
+            "example.com,Example.NET".parse::<EmailDomains>()?,
+            EmailDomains(vec!["example.com".to_owned(), "example.net".to_owned()])
+        );
+        assert!("".parse::<EmailDomains>().is_err());
+        assert!("example.com,".parse::<EmailDomains>().is_err());
+        assert!("a@example.com".parse::<EmailDomains>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_part() -> anyhow::Result<()> {
+        assert_eq!(EmailGenerator::local_part("おおぬき")?, "onuki");
+        // a long vowel followed by a vowel
+        assert_eq!(EmailGenerator::local_part("ゆういち")?, "yuichi");
+        assert_eq!(EmailGenerator::local_part("いのうえ")?, "inoue");
+        assert_eq!(EmailGenerator::local_part("じゅんいち")?, "junichi");
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_without_kana() -> anyhow::Result<()> {
+        let mut generator = EmailGenerator::new("example.com".parse()?);
+        let mut rng = StdRng::seed_from_u64(1);
+        let date_of_birth = "1985-04-01".parse()?;
+        let no_first_name_kana = Name {
+            first_name_kana: "".to_owned(),
+            ..name()
+        };
+        for _ in 0..10 {
+            let email = generator.generate(&no_first_name_kana, date_of_birth, &mut rng)?;
+            assert!(email.starts_with("onuki1985"), "{email}");
+        }
+        let no_last_name_kana = Name {
+            last_name_kana: "".to_owned(),
+            ..name()
+        };
+        assert!(generator
+            .generate(&no_last_name_kana, date_of_birth, &mut rng)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate() -> anyhow::Result<()> {
+        let mut generator = EmailGenerator::new("example.com".parse()?);
+        let mut rng = StdRng::seed_from_u64(1);
+        let date_of_birth = "1985-04-01".parse()?;
+        let expected = [
+            "taro.onuki@example.com",
+            "t.onuki@example.com",
+            "onuki1985@example.com",
+            "onuki_taro@example.com",
+        ];
+        let mut emails = HashSet::new();
+        for _ in 0..100 {
+            let email = generator.generate(&name(), date_of_birth, &mut rng)?;
+            let (local_part, _) = email.split_once('@').expect("email has @");
+            assert!(
+                expected.iter().any(|expected| {
+                    let (expected, _) = expected.split_once('@').expect("email has @");
+                    local_part.starts_with(expected)
+                }),
+                "{email}"
+            );
+            assert!(emails.insert(email));
+        }
+        Ok(())
+    }
+}
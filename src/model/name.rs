@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-#[derive(Clone, Debug, serde::Serialize)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct Name {
     pub first_name: String,
     pub first_name_kana: String,
@@ -11,21 +11,40 @@ pub struct Name {
 impl Name {
     pub fn in_katakana(self) -> Self {
         Self {
-            first_name_kana: Self::hiragana_to_katakana(&self.first_name_kana).unwrap(),
-            last_name_kana: Self::hiragana_to_katakana(&self.last_name_kana).unwrap(),
+            first_name_kana: Self::hiragana_to_katakana(&self.first_name_kana)
+                .expect("validated kana are hiragana"),
+            last_name_kana: Self::hiragana_to_katakana(&self.last_name_kana)
+                .expect("validated kana are hiragana"),
             ..self
         }
     }
 
     pub fn in_halfwidth_kana(self) -> Self {
         Self {
-            first_name_kana: Self::hiragana_to_halfwidth_kana(&self.first_name_kana).unwrap(),
-            last_name_kana: Self::hiragana_to_halfwidth_kana(&self.last_name_kana).unwrap(),
+            first_name_kana: Self::hiragana_to_halfwidth_kana(&self.first_name_kana)
+                .expect("validated kana are hiragana"),
+            last_name_kana: Self::hiragana_to_halfwidth_kana(&self.last_name_kana)
+                .expect("validated kana are hiragana"),
             ..self
         }
     }
 
-    fn hiragana_to_halfwidth_kana(s: &str) -> anyhow::Result<String> {
+    /// Checks that no field is empty and the kana are hiragana
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !self.first_name.is_empty() && !self.last_name.is_empty(),
+            "name is empty"
+        );
+        anyhow::ensure!(
+            !self.first_name_kana.is_empty() && !self.last_name_kana.is_empty(),
+            "kana is empty"
+        );
+        Self::hiragana_to_katakana(&self.last_name_kana)?;
+        Self::hiragana_to_katakana(&self.first_name_kana)?;
+        Ok(())
+    }
+
+    pub(super) fn hiragana_to_halfwidth_kana(s: &str) -> anyhow::Result<String> {
         let map = {
             let mut map = HashMap::new();
             map.insert('ぁ', "ｧ");
@@ -127,7 +146,7 @@ impl Name {
         })
     }
 
-    fn hiragana_to_katakana(s: &str) -> anyhow::Result<String> {
+    pub(super) fn hiragana_to_katakana(s: &str) -> anyhow::Result<String> {
         s.chars()
             .map(|c: char| {
                 let b = c as u32;
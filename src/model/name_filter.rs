@@ -0,0 +1,274 @@
+use std::{borrow::Cow, str::FromStr};
+
+use crate::model::{Charset, Name, Normalization};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NameField {
+    LastName,
+    LastNameKana,
+    FirstName,
+    FirstNameKana,
+}
+
+impl FromStr for NameField {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "last_name" => Ok(Self::LastName),
+            "last_name_kana" => Ok(Self::LastNameKana),
+            "first_name" => Ok(Self::FirstName),
+            "first_name_kana" => Ok(Self::FirstNameKana),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not a valid name field (last_name|last_name_kana|first_name|first_name_kana)"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NameCondition {
+    Contains(String),
+    StartsWith(String),
+    /// The number of characters
+    Length(usize),
+    /// Every character is in the charset
+    Charset(Charset),
+}
+
+impl NameCondition {
+    fn matches(&self, s: &str) -> bool {
+        match self {
+            Self::Contains(value) => s.contains(value.as_str()),
+            Self::StartsWith(value) => s.starts_with(value.as_str()),
+            Self::Length(length) => s.chars().count() == *length,
+            Self::Charset(charset) => charset.find_unrepresentable(s).is_none(),
+        }
+    }
+}
+
+/// Conditions all names must meet, e.g. `last_name:contains:田,first_name_kana:starts_with:か`.
+/// Kana are compared in hiragana.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NameFilter {
+    conditions: Vec<(NameField, NameCondition)>,
+    /// Names are normalized before they are compared
+    normalization: Option<Normalization>,
+}
+
+impl NameFilter {
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    /// Adds the condition that every field is in `charset`
+    pub fn with_charset(mut self, charset: Charset) -> Self {
+        for field in [
+            NameField::LastName,
+            NameField::LastNameKana,
+            NameField::FirstName,
+            NameField::FirstNameKana,
+        ] {
+            self.conditions
+                .push((field, NameCondition::Charset(charset)));
+        }
+        self
+    }
+
+    /// Compares names normalized in the form as they are output
+    pub fn with_normalization(self, normalization: Option<Normalization>) -> Self {
+        Self {
+            normalization,
+            ..self
+        }
+    }
+
+    fn normalize<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        match self.normalization {
+            Some(normalization) => Cow::Owned(normalization.normalize(s)),
+            None => Cow::Borrowed(s),
+        }
+    }
+
+    pub fn matches(&self, name: &Name) -> bool {
+        self.matches_last_name(&name.last_name, &name.last_name_kana)
+            && self.matches_first_name(&name.first_name, &name.first_name_kana)
+    }
+
+    pub fn matches_last_name(&self, last_name: &str, last_name_kana: &str) -> bool {
+        let (last_name, last_name_kana) =
+            (self.normalize(last_name), self.normalize(last_name_kana));
+        self.conditions
+            .iter()
+            .all(|(field, condition)| match field {
+                NameField::LastName => condition.matches(&last_name),
+                NameField::LastNameKana => condition.matches(&last_name_kana),
+                NameField::FirstName | NameField::FirstNameKana => true,
+            })
+    }
+
+    pub fn matches_first_name(&self, first_name: &str, first_name_kana: &str) -> bool {
+        let (first_name, first_name_kana) =
+            (self.normalize(first_name), self.normalize(first_name_kana));
+        self.conditions
+            .iter()
+            .all(|(field, condition)| match field {
+                NameField::FirstName => condition.matches(&first_name),
+                NameField::FirstNameKana => condition.matches(&first_name_kana),
+                NameField::LastName | NameField::LastNameKana => true,
+            })
+    }
+
+    /// The first `contains` condition on `field`, if any
+    pub fn contains(&self, field: NameField) -> Option<&str> {
+        self.conditions
+            .iter()
+            .find_map(|(f, condition)| match condition {
+                NameCondition::Contains(value) if *f == field => Some(value.as_str()),
+                _ => None,
+            })
+    }
+}
+
+impl FromStr for NameFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|item| {
+                let mut parts = item.splitn(3, ':');
+                let (Some(field), Some(op), Some(value)) = (parts.next(), parts.next(), parts.next())
+                else {
+                    anyhow::bail!("{item} is not a valid name filter (field:contains|starts_with|length|charset:value)");
+                };
+                let field = field.parse::<NameField>()?;
+                anyhow::ensure!(!value.is_empty(), "{item} has no value");
+                let condition = match op {
+                    "contains" => NameCondition::Contains(value.to_owned()),
+                    "starts_with" => NameCondition::StartsWith(value.to_owned()),
+                    "charset" => NameCondition::Charset(value.parse::<Charset>()?),
+                    "length" => NameCondition::Length(
+                        value
+                            .parse::<usize>()
+                            .map_err(|_| anyhow::anyhow!("{value} is not a valid length"))?,
+                    ),
+                    _ => anyhow::bail!(
+                        "{op} is not a valid name filter operator (contains|starts_with|length|charset)"
+                    ),
+                };
+                Ok((field, condition))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map(|conditions| Self {
+                conditions,
+                normalization: None,
+            })
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for NameFilter {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<Self>().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        assert_eq!(
+            "last_name:contains:田,first_name_kana:starts_with:か,first_name_kana:length:3"
+                .parse::<NameFilter>()?,
+            NameFilter {
+                conditions: vec![
+                    (
+                        NameField::LastName,
+                        NameCondition::Contains("田".to_owned())
+                    ),
+                    (
+                        NameField::FirstNameKana,
+                        NameCondition::StartsWith("か".to_owned())
+                    ),
+                    (NameField::FirstNameKana, NameCondition::Length(3)),
+                ],
+                normalization: None,
+            }
+        );
+        for (s, message) in [
+            (
+                "last_name:contains",
+                "last_name:contains is not a valid name filter (field:contains|starts_with|length|charset:value)",
+            ),
+            (
+                "sex:contains:f",
+                "sex is not a valid name field (last_name|last_name_kana|first_name|first_name_kana)",
+            ),
+            (
+                "last_name:ends_with:田",
+                "ends_with is not a valid name filter operator (contains|starts_with|length|charset)",
+            ),
+            ("last_name:length:x", "x is not a valid length"),
+            ("last_name:contains:", "last_name:contains: has no value"),
+        ] {
+            assert_eq!(s.parse::<NameFilter>().unwrap_err().to_string(), message);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_matches() -> anyhow::Result<()> {
+        let name = Name {
+            first_name: "和也".to_owned(),
+            first_name_kana: "かずや".to_owned(),
+            last_name: "山田".to_owned(),
+            last_name_kana: "やまだ".to_owned(),
+        };
+        for (filter, expected) in [
+            ("last_name:contains:田", true),
+            ("last_name:contains:中", false),
+            ("first_name_kana:starts_with:か", true),
+            ("first_name_kana:starts_with:や", false),
+            ("last_name_kana:length:3", true),
+            ("last_name:contains:田,first_name_kana:length:2", false),
+            ("last_name:charset:jisx0208-level1", true),
+        ] {
+            assert_eq!(
+                filter.parse::<NameFilter>()?.matches(&name),
+                expected,
+                "{filter}"
+            );
+        }
+        assert!(NameFilter::default().matches(&name));
+        let name = Name {
+            last_name: "髙田".to_owned(),
+            ..name
+        };
+        assert!(NameFilter::default()
+            .with_charset(Charset::Cp932)
+            .matches(&name));
+        assert!(!NameFilter::default()
+            .with_charset(Charset::ShiftJis)
+            .matches(&name));
+
+        // か and the combining voiced sound mark
+        let name = Name {
+            first_name_kana: "か\u{3099}ずや".to_owned(),
+            last_name: "山田".to_owned(),
+            ..name
+        };
+        let filter = "first_name_kana:length:3"
+            .parse::<NameFilter>()?
+            .with_charset(Charset::JisX0208);
+        assert!(!filter.matches(&name));
+        assert!(filter
+            .with_normalization(Some(Normalization::Nfc))
+            .matches(&name));
+        Ok(())
+    }
+}
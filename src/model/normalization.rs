@@ -0,0 +1,91 @@
+use std::str::FromStr;
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::model::Name;
+
+/// A Unicode normalization form
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Normalization {
+    Nfc,
+    /// Also folds compatibility characters, e.g. halfwidth katakana and ㈱
+    Nfkc,
+}
+
+impl Normalization {
+    pub fn normalize(&self, s: &str) -> String {
+        match self {
+            Self::Nfc => s.nfc().collect(),
+            Self::Nfkc => s.nfkc().collect(),
+        }
+    }
+}
+
+impl FromStr for Normalization {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "nfc" => Ok(Self::Nfc),
+            "nfkc" => Ok(Self::Nfkc),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not a valid normalization (nfc|nfkc)"
+            )),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Normalization {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<Self>().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Name {
+    pub fn normalize(self, normalization: Normalization) -> Self {
+        Self {
+            first_name: normalization.normalize(&self.first_name),
+            first_name_kana: normalization.normalize(&self.first_name_kana),
+            last_name: normalization.normalize(&self.last_name),
+            last_name_kana: normalization.normalize(&self.last_name_kana),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        assert_eq!("nfc".parse::<Normalization>()?, Normalization::Nfc);
+        assert_eq!("nfkc".parse::<Normalization>()?, Normalization::Nfkc);
+        assert_eq!(
+            "nfd".parse::<Normalization>().unwrap_err().to_string(),
+            "nfd is not a valid normalization (nfc|nfkc)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize() {
+        let name = Name {
+            // the CJK compatibility ideograph U+FA19
+            first_name: "\u{fa19}子".to_owned(),
+            // か and the combining voiced sound mark
+            first_name_kana: "か\u{3099}みこ".to_owned(),
+            last_name: "㈱".to_owned(),
+            last_name_kana: "かぶしきがいしゃ".to_owned(),
+        };
+        let nfc = name.clone().normalize(Normalization::Nfc);
+        assert_eq!(nfc.first_name, "\u{795e}子");
+        assert_eq!(nfc.first_name_kana, "がみこ");
+        assert_eq!(nfc.last_name, "㈱");
+        let nfkc = name.normalize(Normalization::Nfkc);
+        assert_eq!(nfkc.last_name, "(株)");
+    }
+}
@@ -0,0 +1,217 @@
+use std::str::FromStr;
+
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    seq::SliceRandom,
+    Rng,
+};
+
+/// Area codes with rough weights by the number of subscribers.
+/// Area code + local exchange code is always 6 digits.
+const AREA_CODES: [(&str, u32); 20] = [
+    ("03", 30),
+    ("06", 15),
+    ("011", 5),
+    ("022", 4),
+    ("025", 2),
+    ("043", 3),
+    ("045", 8),
+    ("048", 5),
+    ("052", 7),
+    ("075", 4),
+    ("078", 4),
+    ("082", 3),
+    ("092", 5),
+    ("096", 2),
+    ("098", 2),
+    ("0276", 1),
+    ("0438", 1),
+    ("0466", 1),
+    ("0859", 1),
+    ("04992", 1),
+];
+
+const MOBILE_PREFIXES: [&str; 3] = ["070", "080", "090"];
+
+const IP_PHONE_PREFIX: &str = "050";
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PhoneNumberType {
+    /// Mobile, landline or IP phone weighted by the number of subscribers
+    #[default]
+    Any,
+    /// 070, 080 or 090
+    Mobile,
+    /// Area code aware fixed line
+    Landline,
+    /// 050
+    Ip,
+}
+
+impl FromStr for PhoneNumberType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "any" => Ok(Self::Any),
+            "mobile" => Ok(Self::Mobile),
+            "landline" => Ok(Self::Landline),
+            "ip" => Ok(Self::Ip),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not a valid phone number type (any|mobile|landline|ip)"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PhoneNumberFormat {
+    /// e.g. `03-0123-4567`
+    #[default]
+    Hyphenated,
+    /// e.g. `0301234567`
+    Plain,
+    /// e.g. `+81301234567`
+    E164,
+}
+
+impl FromStr for PhoneNumberFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hyphenated" => Ok(Self::Hyphenated),
+            "plain" => Ok(Self::Plain),
+            "e164" => Ok(Self::E164),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not a valid phone number format (hyphenated|plain|e164)"
+            )),
+        }
+    }
+}
+
+/// A phone number that is never assigned to a subscriber.
+///
+/// Japan has no reserved range for fiction, so the local exchange code (or the digit
+/// after the mobile / IP phone prefix) always starts with 0, which is not in service.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PhoneNumber {
+    prefix: &'static str,
+    exchange: String,
+    subscriber: String,
+}
+
+impl PhoneNumber {
+    pub fn format(&self, format: PhoneNumberFormat) -> String {
+        match format {
+            PhoneNumberFormat::Hyphenated => {
+                format!("{}-{}-{}", self.prefix, self.exchange, self.subscriber)
+            }
+            PhoneNumberFormat::Plain => {
+                format!("{}{}{}", self.prefix, self.exchange, self.subscriber)
+            }
+            PhoneNumberFormat::E164 => format!(
+                "+81{}{}{}",
+                &self.prefix[1..],
+                self.exchange,
+                self.subscriber
+            ),
+        }
+    }
+}
+
+fn digits<R: Rng + ?Sized>(rng: &mut R, len: usize) -> String {
+    (0..len)
+        .map(|_| char::from(b'0' + rng.gen_range(0..10)))
+        .collect()
+}
+
+impl Distribution<PhoneNumber> for PhoneNumberType {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> PhoneNumber {
+        let (prefix, exchange_len) = match self {
+            Self::Any => {
+                let types = [(Self::Mobile, 6), (Self::Landline, 3), (Self::Ip, 1)];
+                let (t, _) = types
+                    .choose_weighted(rng, |(_, w)| *w)
+                    .expect("valid weights");
+                return t.sample(rng);
+            }
+            Self::Mobile => (*MOBILE_PREFIXES.choose(rng).expect("not empty"), 4),
+            Self::Landline => {
+                let weights =
+                    WeightedIndex::new(AREA_CODES.iter().map(|(_, w)| *w)).expect("valid weights");
+                let (area_code, _) = AREA_CODES[weights.sample(rng)];
+                (area_code, 6 - area_code.len())
+            }
+            Self::Ip => (IP_PHONE_PREFIX, 4),
+        };
+        PhoneNumber {
+            prefix,
+            exchange: format!("0{}", digits(rng, exchange_len - 1)),
+            subscriber: digits(rng, 4),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        assert_eq!(
+            "mobile".parse::<PhoneNumberType>()?,
+            PhoneNumberType::Mobile
+        );
+        assert_eq!("ip".parse::<PhoneNumberType>()?, PhoneNumberType::Ip);
+        assert!("fax".parse::<PhoneNumberType>().is_err());
+        assert_eq!(
+            "e164".parse::<PhoneNumberFormat>()?,
+            PhoneNumberFormat::E164
+        );
+        assert!("dotted".parse::<PhoneNumberFormat>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_format() {
+        let phone_number = PhoneNumber {
+            prefix: "03",
+            exchange: "0123".to_owned(),
+            subscriber: "4567".to_owned(),
+        };
+        assert_eq!(
+            phone_number.format(PhoneNumberFormat::Hyphenated),
+            "03-0123-4567"
+        );
+        assert_eq!(phone_number.format(PhoneNumberFormat::Plain), "0301234567");
+        assert_eq!(phone_number.format(PhoneNumberFormat::E164), "+81301234567");
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            let mobile = rng.sample(PhoneNumberType::Mobile);
+            assert_eq!(mobile.format(PhoneNumberFormat::Plain).len(), 11);
+            assert!(MOBILE_PREFIXES.contains(&mobile.prefix));
+            assert!(mobile.exchange.starts_with('0'));
+
+            let landline = rng.sample(PhoneNumberType::Landline);
+            assert_eq!(landline.format(PhoneNumberFormat::Plain).len(), 10);
+            assert!(landline.exchange.starts_with('0'));
+
+            let ip = rng.sample(PhoneNumberType::Ip);
+            assert!(ip
+                .format(PhoneNumberFormat::Hyphenated)
+                .starts_with("050-0"));
+
+            let any = rng.sample(PhoneNumberType::Any);
+            assert!(any.format(PhoneNumberFormat::E164).starts_with("+81"));
+        }
+    }
+}
@@ -1,45 +1,178 @@
-use crate::model::{DateOfBirth, Name, Sex};
+use rand::RngCore;
+
+use crate::model::{Address, DateOfBirth, DateOfBirthRangeError, Name, NameFilter, Rarity, Sex};
 
 #[derive(Debug, serde::Serialize)]
 pub struct PI {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age: Option<u16>,
     pub date_of_birth: DateOfBirth,
+    /// e.g. `令和元年5月1日`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_of_birth_wareki: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day_of_week: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
     pub first_name: String,
     pub first_name_kana: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_name_romaji: Option<String>,
     pub last_name: String,
     pub last_name_kana: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_name_romaji: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone_number: Option<String>,
     pub sex: Sex,
+    /// The name source which generated the name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub star_sign: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zodiac: Option<String>,
+    #[serde(flatten)]
+    pub address: Option<Address>,
+}
+
+impl PI {
+    /// Every key of a serialized PI. Optional fields are skipped when they are `None`
+    pub const FIELDS: &'static [&'static str] = &[
+        "age",
+        "date_of_birth",
+        "date_of_birth_wareki",
+        "day_of_week",
+        "email",
+        "first_name",
+        "first_name_kana",
+        "first_name_romaji",
+        "last_name",
+        "last_name_kana",
+        "last_name_romaji",
+        "phone_number",
+        "sex",
+        "source",
+        "star_sign",
+        "zodiac",
+        "postal_code",
+        "prefecture",
+        "prefecture_kana",
+        "city",
+        "city_kana",
+        "town",
+        "town_kana",
+        "block_number",
+        "building",
+    ];
 }
 
 impl From<(Name, Sex, DateOfBirth)> for PI {
     fn from((name, sex, date_of_birth): (Name, Sex, DateOfBirth)) -> Self {
         Self {
+            age: None,
             date_of_birth,
+            date_of_birth_wareki: None,
+            day_of_week: None,
+            email: None,
             first_name: name.first_name,
             first_name_kana: name.first_name_kana,
+            first_name_romaji: None,
             last_name: name.last_name,
             last_name_kana: name.last_name_kana,
+            last_name_romaji: None,
+            phone_number: None,
             sex,
+            source: None,
+            star_sign: None,
+            zodiac: None,
+            address: None,
         }
     }
 }
 
 #[derive(Clone, Debug, thiserror::Error)]
 pub enum GenNameError {
-    #[error("request failure")]
-    RequestFailure,
-    #[error("conflict")]
-    Conflict,
+    #[error("failed to request namegen.jp: {0}")]
+    Network(String),
+    #[error("namegen.jp timed out")]
+    Timeout,
+    #[error("namegen.jp responded with {0}")]
+    Status(u16),
+    #[error("failed to parse names: {0}")]
+    Parse(String),
+    #[error("no cached names available offline")]
+    Offline,
+    #[error("no names available")]
+    NoNames,
+    #[error("no names match the filter")]
+    NoMatch,
+    #[error("the name source has no {} names", .0.name())]
+    UnsupportedRarity(Rarity),
 }
 
 #[derive(Clone, Debug, thiserror::Error)]
 pub enum GenPiError {
     #[error("gen name error")]
     GenNameError(GenNameError),
+    #[error("invalid date of birth range: {0}")]
+    DateOfBirthRange(DateOfBirthRangeError),
+    #[error("romanization failure: {0}")]
+    Romanization(String),
+    #[error("email generation failure: {0}")]
+    Email(String),
+    #[error("wareki conversion failure: {0}")]
+    Wareki(String),
+    #[error("charset conflict: {0}")]
+    Charset(String),
 }
 
 #[async_trait::async_trait]
 pub trait NameGenerator {
-    async fn generate(&self, sex: Sex) -> Result<Name, GenNameError>;
+    async fn generate(
+        &self,
+        sex: Sex,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<Name, GenNameError>;
+
+    /// Generates names matching `filter`.
+    /// The default implementation calls `generate` until a name matches.
+    async fn generate_many(
+        &self,
+        sex: Sex,
+        count: usize,
+        filter: &NameFilter,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<Vec<Name>, GenNameError> {
+        const MAX_ATTEMPTS: usize = 100;
+        let mut names = Vec::with_capacity(count);
+        for _ in 0..count {
+            let mut attempts = 0;
+            let name = loop {
+                let name = self.generate(sex, rng).await?;
+                if filter.matches(&name) {
+                    break name;
+                }
+                attempts += 1;
+                if attempts >= MAX_ATTEMPTS {
+                    return Err(GenNameError::NoMatch);
+                }
+            };
+            names.push(name);
+        }
+        Ok(names)
+    }
+
+    /// Also returns the name of the source which generated the names, if any
+    async fn generate_many_with_source(
+        &self,
+        sex: Sex,
+        count: usize,
+        filter: &NameFilter,
+        rng: &mut (dyn RngCore + Send),
+    ) -> Result<(Vec<Name>, Option<&'static str>), GenNameError> {
+        Ok((self.generate_many(sex, count, filter, rng).await?, None))
+    }
 }
 
 pub trait HasNameGenerator {
@@ -47,9 +180,67 @@ pub trait HasNameGenerator {
     fn name_generator(&self) -> &Self::NameGenerator;
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum KanaForm {
+    #[default]
     Hiragana,
     Katakana,
     HalfwidthKana,
 }
+
+impl KanaForm {
+    /// Converts hiragana into the form
+    pub fn convert(&self, hiragana: &str) -> anyhow::Result<String> {
+        match self {
+            // converts to katakana only to check that it is hiragana
+            Self::Hiragana => Name::hiragana_to_katakana(hiragana).map(|_| hiragana.to_owned()),
+            Self::Katakana => Name::hiragana_to_katakana(hiragana),
+            Self::HalfwidthKana => Name::hiragana_to_halfwidth_kana(hiragana),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_fields() -> anyhow::Result<()> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let name = Name {
+            first_name: "太郎".to_owned(),
+            first_name_kana: "たろう".to_owned(),
+            last_name: "山田".to_owned(),
+            last_name_kana: "やまだ".to_owned(),
+        };
+        let mut pi = PI::from((name, Sex::Male, "2000-01-01".parse()?));
+        let some = Some(String::new());
+        pi.age = Some(20);
+        pi.date_of_birth_wareki = some.clone();
+        pi.day_of_week = some.clone();
+        pi.email = some.clone();
+        pi.first_name_romaji = some.clone();
+        pi.last_name_romaji = some.clone();
+        pi.phone_number = some.clone();
+        pi.source = some.clone();
+        pi.star_sign = some.clone();
+        pi.zodiac = some.clone();
+        pi.address = Some(Address {
+            building: some,
+            ..rng.gen()
+        });
+        let keys = match serde_json::to_value(&pi)? {
+            serde_json::Value::Object(map) => map.keys().cloned().collect::<Vec<String>>(),
+            _ => unreachable!(),
+        };
+        let mut fields = PI::FIELDS.to_vec();
+        fields.sort_unstable();
+        let mut keys = keys.iter().map(String::as_str).collect::<Vec<&str>>();
+        keys.sort_unstable();
+        assert_eq!(keys, fields);
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use std::str::FromStr;
+
+/// How rare generated names are
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Rarity {
+    #[default]
+    Any,
+    Common,
+    Rare,
+}
+
+impl Rarity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Any => "any",
+            Self::Common => "common",
+            Self::Rare => "rare",
+        }
+    }
+}
+
+impl FromStr for Rarity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "any" => Ok(Self::Any),
+            "common" => Ok(Self::Common),
+            "rare" => Ok(Self::Rare),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not a valid rarity (any|common|rare)"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        assert_eq!("any".parse::<Rarity>()?, Rarity::Any);
+        assert_eq!("common".parse::<Rarity>()?, Rarity::Common);
+        assert_eq!("rare".parse::<Rarity>()?, Rarity::Rare);
+        assert_eq!(
+            "foo".parse::<Rarity>().unwrap_err().to_string(),
+            "foo is not a valid rarity (any|common|rare)"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,335 @@
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Romanization {
+    /// Modified Hepburn with macrons (e.g. Ōnuki, Jun'ichi)
+    #[default]
+    Hepburn,
+    /// Hepburn for Japanese passports without long vowels (e.g. Onuki, Homma)
+    Passport,
+    /// Kunrei-shiki with circumflexes (e.g. Ônuki, Zyun'iti)
+    Kunrei,
+}
+
+impl FromStr for Romanization {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hepburn" => Ok(Self::Hepburn),
+            "passport" => Ok(Self::Passport),
+            "kunrei" => Ok(Self::Kunrei),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not a valid romanization (hepburn|passport|kunrei)"
+            )),
+        }
+    }
+}
+
+/// `(hiragana, Hepburn, Kunrei-shiki)`. Longer kana must come first.
+const MORAE: &[(&str, &str, &str)] = &[
+    ("きゃ", "kya", "kya"),
+    ("きゅ", "kyu", "kyu"),
+    ("きょ", "kyo", "kyo"),
+    ("ぎゃ", "gya", "gya"),
+    ("ぎゅ", "gyu", "gyu"),
+    ("ぎょ", "gyo", "gyo"),
+    ("しゃ", "sha", "sya"),
+    ("しゅ", "shu", "syu"),
+    ("しぇ", "she", "sye"),
+    ("しょ", "sho", "syo"),
+    ("じゃ", "ja", "zya"),
+    ("じゅ", "ju", "zyu"),
+    ("じぇ", "je", "zye"),
+    ("じょ", "jo", "zyo"),
+    ("ちゃ", "cha", "tya"),
+    ("ちゅ", "chu", "tyu"),
+    ("ちぇ", "che", "tye"),
+    ("ちょ", "cho", "tyo"),
+    ("ぢゃ", "ja", "zya"),
+    ("ぢゅ", "ju", "zyu"),
+    ("ぢょ", "jo", "zyo"),
+    ("てぃ", "ti", "ti"),
+    ("でぃ", "di", "di"),
+    ("にゃ", "nya", "nya"),
+    ("にゅ", "nyu", "nyu"),
+    ("にょ", "nyo", "nyo"),
+    ("ひゃ", "hya", "hya"),
+    ("ひゅ", "hyu", "hyu"),
+    ("ひょ", "hyo", "hyo"),
+    ("びゃ", "bya", "bya"),
+    ("びゅ", "byu", "byu"),
+    ("びょ", "byo", "byo"),
+    ("ぴゃ", "pya", "pya"),
+    ("ぴゅ", "pyu", "pyu"),
+    ("ぴょ", "pyo", "pyo"),
+    ("ふぁ", "fa", "fa"),
+    ("ふぃ", "fi", "fi"),
+    ("ふぇ", "fe", "fe"),
+    ("ふぉ", "fo", "fo"),
+    ("みゃ", "mya", "mya"),
+    ("みゅ", "myu", "myu"),
+    ("みょ", "myo", "myo"),
+    ("りゃ", "rya", "rya"),
+    ("りゅ", "ryu", "ryu"),
+    ("りょ", "ryo", "ryo"),
+    ("うぃ", "wi", "wi"),
+    ("うぇ", "we", "we"),
+    ("うぉ", "wo", "wo"),
+    ("ゔぁ", "va", "va"),
+    ("ゔぃ", "vi", "vi"),
+    ("ゔぇ", "ve", "ve"),
+    ("ゔぉ", "vo", "vo"),
+    ("あ", "a", "a"),
+    ("い", "i", "i"),
+    ("う", "u", "u"),
+    ("え", "e", "e"),
+    ("お", "o", "o"),
+    ("か", "ka", "ka"),
+    ("き", "ki", "ki"),
+    ("く", "ku", "ku"),
+    ("け", "ke", "ke"),
+    ("こ", "ko", "ko"),
+    ("が", "ga", "ga"),
+    ("ぎ", "gi", "gi"),
+    ("ぐ", "gu", "gu"),
+    ("げ", "ge", "ge"),
+    ("ご", "go", "go"),
+    ("さ", "sa", "sa"),
+    ("し", "shi", "si"),
+    ("す", "su", "su"),
+    ("せ", "se", "se"),
+    ("そ", "so", "so"),
+    ("ざ", "za", "za"),
+    ("じ", "ji", "zi"),
+    ("ず", "zu", "zu"),
+    ("ぜ", "ze", "ze"),
+    ("ぞ", "zo", "zo"),
+    ("た", "ta", "ta"),
+    ("ち", "chi", "ti"),
+    ("つ", "tsu", "tu"),
+    ("て", "te", "te"),
+    ("と", "to", "to"),
+    ("だ", "da", "da"),
+    ("ぢ", "ji", "zi"),
+    ("づ", "zu", "zu"),
+    ("で", "de", "de"),
+    ("ど", "do", "do"),
+    ("な", "na", "na"),
+    ("に", "ni", "ni"),
+    ("ぬ", "nu", "nu"),
+    ("ね", "ne", "ne"),
+    ("の", "no", "no"),
+    ("は", "ha", "ha"),
+    ("ひ", "hi", "hi"),
+    ("ふ", "fu", "hu"),
+    ("へ", "he", "he"),
+    ("ほ", "ho", "ho"),
+    ("ば", "ba", "ba"),
+    ("び", "bi", "bi"),
+    ("ぶ", "bu", "bu"),
+    ("べ", "be", "be"),
+    ("ぼ", "bo", "bo"),
+    ("ぱ", "pa", "pa"),
+    ("ぴ", "pi", "pi"),
+    ("ぷ", "pu", "pu"),
+    ("ぺ", "pe", "pe"),
+    ("ぽ", "po", "po"),
+    ("ま", "ma", "ma"),
+    ("み", "mi", "mi"),
+    ("む", "mu", "mu"),
+    ("め", "me", "me"),
+    ("も", "mo", "mo"),
+    ("や", "ya", "ya"),
+    ("ゆ", "yu", "yu"),
+    ("よ", "yo", "yo"),
+    ("ら", "ra", "ra"),
+    ("り", "ri", "ri"),
+    ("る", "ru", "ru"),
+    ("れ", "re", "re"),
+    ("ろ", "ro", "ro"),
+    ("わ", "wa", "wa"),
+    ("ゐ", "i", "i"),
+    ("ゑ", "e", "e"),
+    ("を", "o", "o"),
+    ("ゔ", "vu", "vu"),
+    ("ぁ", "a", "a"),
+    ("ぃ", "i", "i"),
+    ("ぅ", "u", "u"),
+    ("ぇ", "e", "e"),
+    ("ぉ", "o", "o"),
+    ("ゃ", "ya", "ya"),
+    ("ゅ", "yu", "yu"),
+    ("ょ", "yo", "yo"),
+    ("ゎ", "wa", "wa"),
+    ("ゕ", "ka", "ka"),
+    ("ゖ", "ke", "ke"),
+];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Mora {
+    Syllable(&'static str),
+    /// っ
+    Sokuon,
+    /// ん
+    Hatsuon,
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'i' | 'u' | 'e' | 'o')
+}
+
+impl Romanization {
+    /// Converts hiragana into romaji. The first letter is capitalized.
+    pub fn romanize(&self, hiragana: &str) -> anyhow::Result<String> {
+        let morae = self.split_into_morae(hiragana)?;
+        let mut romaji = String::new();
+        let mut i = 0;
+        while i < morae.len() {
+            let next = match morae.get(i + 1) {
+                Some(Mora::Syllable(s)) => s.chars().next(),
+                _ => None,
+            };
+            match morae[i] {
+                Mora::Sokuon => match morae.get(i + 1) {
+                    Some(Mora::Syllable(s))
+                        if s.starts_with("ch") && *self != Romanization::Kunrei =>
+                    {
+                        romaji.push('t')
+                    }
+                    Some(Mora::Syllable(s)) if !s.starts_with(is_vowel) => {
+                        romaji.push(s.chars().next().expect("syllable is not empty"))
+                    }
+                    _ => {}
+                },
+                Mora::Hatsuon => match (self, next) {
+                    (Romanization::Passport, Some('b' | 'm' | 'p')) => romaji.push('m'),
+                    (Romanization::Passport, _) => romaji.push('n'),
+                    (_, Some('a' | 'i' | 'u' | 'e' | 'o' | 'y')) => romaji.push_str("n'"),
+                    (_, _) => romaji.push('n'),
+                },
+                Mora::Syllable(s) => {
+                    romaji.push_str(s);
+                    if self.is_long_vowel(&morae[i..]) {
+                        romaji.pop();
+                        match (self, s.chars().last()) {
+                            (Romanization::Hepburn, Some('o')) => romaji.push('ō'),
+                            (Romanization::Hepburn, Some('u')) => romaji.push('ū'),
+                            (Romanization::Kunrei, Some('o')) => romaji.push('ô'),
+                            (Romanization::Kunrei, Some('u')) => romaji.push('û'),
+                            (_, Some(vowel)) => romaji.push(vowel),
+                            (_, None) => {}
+                        }
+                        i += 1;
+                    }
+                }
+            }
+            i += 1;
+        }
+        let mut chars = romaji.chars();
+        Ok(match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => romaji,
+        })
+    }
+
+    fn split_into_morae(&self, hiragana: &str) -> anyhow::Result<Vec<Mora>> {
+        let mut morae = vec![];
+        let mut rest = hiragana;
+        while let Some(c) = rest.chars().next() {
+            let mora = match c {
+                'っ' => Mora::Sokuon,
+                'ん' => Mora::Hatsuon,
+                _ => {
+                    let (kana, hepburn, kunrei) = MORAE
+                        .iter()
+                        .find(|(kana, _, _)| rest.starts_with(kana))
+                        .ok_or_else(|| anyhow::anyhow!("{} is not hiragana", c))?;
+                    rest = &rest[kana.len()..];
+                    morae.push(Mora::Syllable(match self {
+                        Romanization::Hepburn | Romanization::Passport => hepburn,
+                        Romanization::Kunrei => kunrei,
+                    }));
+                    continue;
+                }
+            };
+            rest = &rest[c.len_utf8()..];
+            morae.push(mora);
+        }
+        Ok(morae)
+    }
+
+    /// Returns true if `morae[0]` and `morae[1]` form a long vowel (おう, おお or うう).
+    ///
+    /// う followed by え is not treated as a long vowel
+    /// because うえ is likely to be 上 (e.g. いのうえ is Inoue, not Inōe).
+    fn is_long_vowel(&self, morae: &[Mora]) -> bool {
+        let (Some(Mora::Syllable(current)), Some(Mora::Syllable(next))) =
+            (morae.first(), morae.get(1))
+        else {
+            return false;
+        };
+        let ue =
+            *next == "u" && matches!(morae.get(2), Some(Mora::Syllable(s)) if s.starts_with('e'));
+        !ue && matches!(
+            (current.chars().last(), *next),
+            (Some('o'), "u" | "o") | (Some('u'), "u")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        assert_eq!("hepburn".parse::<Romanization>()?, Romanization::Hepburn);
+        assert_eq!("passport".parse::<Romanization>()?, Romanization::Passport);
+        assert_eq!("kunrei".parse::<Romanization>()?, Romanization::Kunrei);
+        assert!("nihon".parse::<Romanization>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_romanize() -> anyhow::Result<()> {
+        let table = [
+            // (hiragana, Hepburn, passport, Kunrei-shiki)
+            ("おおぬき", "Ōnuki", "Onuki", "Ônuki"),
+            ("さとう", "Satō", "Sato", "Satô"),
+            ("ゆうこ", "Yūko", "Yuko", "Yûko"),
+            ("いのうえ", "Inoue", "Inoue", "Inoue"),
+            ("ゆういち", "Yūichi", "Yuichi", "Yûiti"),
+            ("こういち", "Kōichi", "Koichi", "Kôiti"),
+            ("しゅういち", "Shūichi", "Shuichi", "Syûiti"),
+            ("おおうち", "Ōuchi", "Ouchi", "Ôuti"),
+            ("いいだ", "Iida", "Iida", "Iida"),
+            ("けいこ", "Keiko", "Keiko", "Keiko"),
+            ("はっとり", "Hattori", "Hattori", "Hattori"),
+            ("はっちょう", "Hatchō", "Hatcho", "Hattyô"),
+            ("ほんま", "Honma", "Homma", "Honma"),
+            ("なんば", "Nanba", "Namba", "Nanba"),
+            ("じゅんいち", "Jun'ichi", "Junichi", "Zyun'iti"),
+            ("しんや", "Shin'ya", "Shinya", "Sin'ya"),
+            ("ちひろ", "Chihiro", "Chihiro", "Tihiro"),
+            ("つばさ", "Tsubasa", "Tsubasa", "Tubasa"),
+            ("ふじた", "Fujita", "Fujita", "Huzita"),
+            ("しょうた", "Shōta", "Shota", "Syôta"),
+            ("", "", "", ""),
+        ];
+        for (hiragana, hepburn, passport, kunrei) in table {
+            assert_eq!(Romanization::Hepburn.romanize(hiragana)?, hepburn);
+            assert_eq!(Romanization::Passport.romanize(hiragana)?, passport);
+            assert_eq!(Romanization::Kunrei.romanize(hiragana)?, kunrei);
+        }
+        assert_eq!(
+            Romanization::Hepburn
+                .romanize("ヤマダ")
+                .unwrap_err()
+                .to_string(),
+            "ヤ is not hiragana"
+        );
+        Ok(())
+    }
+}
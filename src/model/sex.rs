@@ -1,10 +1,58 @@
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, serde::Serialize)]
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Sex {
     Female,
     Male,
 }
 
+impl FromStr for Sex {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "female" => Ok(Self::Female),
+            "male" => Ok(Self::Male),
+            _ => Err(anyhow::anyhow!("{s} is not a valid sex (female|male)")),
+        }
+    }
+}
+
+/// The probability of `Sex::Female` (0.0..=1.0)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FemaleRatio(f64);
+
+impl FromStr for FemaleRatio {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ratio = s
+            .parse::<f64>()
+            .ok()
+            .filter(|ratio| (0.0..=1.0).contains(ratio))
+            .ok_or_else(|| anyhow::anyhow!("female ratio range is (0.0..=1.0)"))?;
+        Ok(Self(ratio))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for FemaleRatio {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl rand::distributions::Distribution<Sex> for FemaleRatio {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Sex {
+        if rng.gen_bool(self.0) {
+            Sex::Female
+        } else {
+            Sex::Male
+        }
+    }
+}
+
 impl rand::distributions::Distribution<Sex> for rand::distributions::Standard {
     fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Sex {
         if rng.gen::<bool>() {
@@ -23,6 +71,32 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        assert_eq!("female".parse::<Sex>()?, Sex::Female);
+        assert_eq!("male".parse::<Sex>()?, Sex::Male);
+        assert!("other".parse::<Sex>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_female_ratio() -> anyhow::Result<()> {
+        let mut rng = rand::thread_rng();
+        let female_ratio = "1".parse::<FemaleRatio>()?;
+        assert!((0..100).all(|_| rng.sample(female_ratio) == Sex::Female));
+        let female_ratio = "0.0".parse::<FemaleRatio>()?;
+        assert!((0..100).all(|_| rng.sample(female_ratio) == Sex::Male));
+        let female_ratio = "0.5".parse::<FemaleRatio>()?;
+        let set = (0..100)
+            .map(|_| rng.sample(female_ratio))
+            .collect::<HashSet<Sex>>();
+        assert_eq!(set.len(), 2);
+        assert!("1.1".parse::<FemaleRatio>().is_err());
+        assert!("-0.1".parse::<FemaleRatio>().is_err());
+        assert!("a".parse::<FemaleRatio>().is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_sample() {
         let mut rng = rand::thread_rng();
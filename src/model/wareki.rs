@@ -0,0 +1,254 @@
+use std::{fmt, str::FromStr};
+
+use time::{macros::date, Date, Month};
+
+/// `(era, the first day)` in chronological order
+const ERAS: [(Era, Date); 5] = [
+    (Era::Meiji, date!(1868 - 10 - 23)),
+    (Era::Taisho, date!(1912 - 07 - 30)),
+    (Era::Showa, date!(1926 - 12 - 25)),
+    (Era::Heisei, date!(1989 - 01 - 08)),
+    (Era::Reiwa, date!(2019 - 05 - 01)),
+];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Era {
+    Meiji,
+    Taisho,
+    Showa,
+    Heisei,
+    Reiwa,
+}
+
+impl Era {
+    /// e.g. `令和`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Meiji => "明治",
+            Self::Taisho => "大正",
+            Self::Showa => "昭和",
+            Self::Heisei => "平成",
+            Self::Reiwa => "令和",
+        }
+    }
+
+    /// e.g. `R`
+    pub fn abbreviation(&self) -> char {
+        match self {
+            Self::Meiji => 'M',
+            Self::Taisho => 'T',
+            Self::Showa => 'S',
+            Self::Heisei => 'H',
+            Self::Reiwa => 'R',
+        }
+    }
+
+    fn start(&self) -> Date {
+        ERAS.iter()
+            .find(|(era, _)| era == self)
+            .map(|(_, start)| *start)
+            .expect("all eras are listed")
+    }
+
+    /// The last day of the era. `None` for the current era
+    fn end(&self) -> Option<Date> {
+        ERAS.iter()
+            .skip_while(|(era, _)| era != self)
+            .nth(1)
+            .map(|(_, start)| start.previous_day().expect("valid date"))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WarekiFormat {
+    /// e.g. `令和元年5月1日`
+    #[default]
+    Kanji,
+    /// e.g. `R1.5.1`
+    Abbreviated,
+}
+
+impl FromStr for WarekiFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "kanji" => Ok(Self::Kanji),
+            "abbreviated" => Ok(Self::Abbreviated),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not a valid wareki format (kanji|abbreviated)"
+            )),
+        }
+    }
+}
+
+/// A date in the Japanese calendar (和暦) from Meiji to Reiwa.
+///
+/// Dates before 1873 are converted from the Gregorian calendar as is,
+/// although Japan used the lunisolar calendar until then.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Wareki {
+    pub era: Era,
+    /// 1 is 元年
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl TryFrom<Date> for Wareki {
+    type Error = anyhow::Error;
+
+    fn try_from(date: Date) -> Result<Self, Self::Error> {
+        let (era, start) = ERAS
+            .iter()
+            .rev()
+            .find(|(_, start)| *start <= date)
+            .ok_or_else(|| anyhow::anyhow!("{date} is before Meiji"))?;
+        Ok(Self {
+            era: *era,
+            year: u16::try_from(date.year() - start.year() + 1)?,
+            month: u8::from(date.month()),
+            day: date.day(),
+        })
+    }
+}
+
+impl TryFrom<Wareki> for Date {
+    type Error = anyhow::Error;
+
+    fn try_from(wareki: Wareki) -> Result<Self, Self::Error> {
+        let year = wareki.era.start().year() + i32::from(wareki.year) - 1;
+        let date = Date::from_calendar_date(year, Month::try_from(wareki.month)?, wareki.day)?;
+        let in_era = wareki.year > 0
+            && wareki.era.start() <= date
+            && wareki.era.end().into_iter().all(|end| date <= end);
+        anyhow::ensure!(in_era, "{wareki} is not in {}", wareki.era.name());
+        Ok(date)
+    }
+}
+
+impl FromStr for Wareki {
+    type Err = anyhow::Error;
+
+    /// Parses `令和元年5月1日`, `令和1年5月1日`, `R1.5.1`, `R01.05.01` or `r1/5/1`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || anyhow::anyhow!("{s} is not a valid wareki");
+        let (era, rest) = ERAS
+            .iter()
+            .find_map(|(era, _)| {
+                s.strip_prefix(era.name())
+                    .or_else(|| s.strip_prefix(era.abbreviation()))
+                    .or_else(|| s.strip_prefix(era.abbreviation().to_ascii_lowercase()))
+                    .map(|rest| (*era, rest))
+            })
+            .ok_or_else(invalid)?;
+        let numbers = match rest.strip_suffix('日') {
+            Some(rest) => {
+                let (year, rest) = rest.split_once('年').ok_or_else(invalid)?;
+                let (month, day) = rest.split_once('月').ok_or_else(invalid)?;
+                [if year == "元" { "1" } else { year }, month, day]
+            }
+            None => {
+                let parts = rest.split(['.', '/', '-']).collect::<Vec<&str>>();
+                <[&str; 3]>::try_from(parts).map_err(|_| invalid())?
+            }
+        };
+        let [year, month, day] = numbers;
+        let wareki = Self {
+            era,
+            year: year.parse().map_err(|_| invalid())?,
+            month: month.parse().map_err(|_| invalid())?,
+            day: day.parse().map_err(|_| invalid())?,
+        };
+        // validates that the date is in the era
+        Date::try_from(wareki)?;
+        Ok(wareki)
+    }
+}
+
+impl fmt::Display for Wareki {
+    /// e.g. `令和元年5月1日`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.era.name())?;
+        match self.year {
+            1 => write!(f, "元年")?,
+            year => write!(f, "{year}年")?,
+        }
+        write!(f, "{}月{}日", self.month, self.day)
+    }
+}
+
+impl Wareki {
+    /// e.g. `R1.5.1`
+    pub fn abbreviated(&self) -> String {
+        format!(
+            "{}{}.{}.{}",
+            self.era.abbreviation(),
+            self.year,
+            self.month,
+            self.day
+        )
+    }
+
+    pub fn format(&self, format: WarekiFormat) -> String {
+        match format {
+            WarekiFormat::Kanji => self.to_string(),
+            WarekiFormat::Abbreviated => self.abbreviated(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_date() -> anyhow::Result<()> {
+        let table = [
+            (date!(1868 - 10 - 23), "明治元年10月23日", "M1.10.23"),
+            (date!(1912 - 07 - 29), "明治45年7月29日", "M45.7.29"),
+            (date!(1912 - 07 - 30), "大正元年7月30日", "T1.7.30"),
+            (date!(1926 - 12 - 25), "昭和元年12月25日", "S1.12.25"),
+            (date!(1989 - 01 - 07), "昭和64年1月7日", "S64.1.7"),
+            (date!(1989 - 01 - 08), "平成元年1月8日", "H1.1.8"),
+            (date!(2019 - 04 - 30), "平成31年4月30日", "H31.4.30"),
+            (date!(2019 - 05 - 01), "令和元年5月1日", "R1.5.1"),
+            (date!(2024 - 02 - 29), "令和6年2月29日", "R6.2.29"),
+        ];
+        for (date, kanji, abbreviated) in table {
+            let wareki = Wareki::try_from(date)?;
+            assert_eq!(wareki.format(WarekiFormat::Kanji), kanji);
+            assert_eq!(wareki.format(WarekiFormat::Abbreviated), abbreviated);
+            assert_eq!(Date::try_from(wareki)?, date);
+            assert_eq!(kanji.parse::<Wareki>()?, wareki);
+            assert_eq!(abbreviated.parse::<Wareki>()?, wareki);
+        }
+        assert!(Wareki::try_from(date!(1868 - 10 - 22)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        let reiwa = Wareki {
+            era: Era::Reiwa,
+            year: 1,
+            month: 5,
+            day: 1,
+        };
+        assert_eq!("令和1年5月1日".parse::<Wareki>()?, reiwa);
+        assert_eq!("R01.05.01".parse::<Wareki>()?, reiwa);
+        assert_eq!("r1/5/1".parse::<Wareki>()?, reiwa);
+        assert_eq!("R1-5-1".parse::<Wareki>()?, reiwa);
+        // not in the era
+        assert!("平成32年1月1日".parse::<Wareki>().is_err());
+        assert!("R1.4.30".parse::<Wareki>().is_err());
+        assert!("H0.1.1".parse::<Wareki>().is_err());
+        // invalid dates
+        assert!("H2.2.30".parse::<Wareki>().is_err());
+        assert!("X1.1.1".parse::<Wareki>().is_err());
+        assert!("令和5月1日".parse::<Wareki>().is_err());
+        assert!("R1.5".parse::<Wareki>().is_err());
+        Ok(())
+    }
+}
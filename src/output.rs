@@ -0,0 +1,422 @@
+mod encoding;
+mod fields;
+mod sql;
+mod template;
+
+use std::str::FromStr;
+
+use anyhow::Context;
+use serde_json::{Map, Value};
+use time::Date;
+
+use crate::model::{DateFormat, DateOfBirth};
+
+pub use self::encoding::*;
+pub use self::fields::*;
+pub use self::sql::*;
+pub use self::template::*;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    Ndjson,
+    Csv,
+    Tsv,
+    Sql,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "ndjson" => Ok(Self::Ndjson),
+            "csv" => Ok(Self::Csv),
+            "tsv" => Ok(Self::Tsv),
+            "sql" => Ok(Self::Sql),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not a valid format (json|ndjson|csv|tsv|sql)"
+            )),
+        }
+    }
+}
+
+impl OutputFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Ndjson => "application/x-ndjson",
+            Self::Csv => "text/csv; charset=utf-8; header=present",
+            Self::Tsv => "text/tab-separated-values; charset=utf-8",
+            Self::Sql => "application/sql; charset=utf-8",
+        }
+    }
+
+    /// JSON must be encoded in UTF-8 (RFC 8259)
+    pub fn allows(&self, encoding: Encoding) -> bool {
+        match self {
+            Self::Json | Self::Ndjson => encoding == Encoding::Utf8,
+            Self::Csv | Self::Tsv | Self::Sql => true,
+        }
+    }
+
+    /// The content type with the charset of `encoding`
+    pub fn content_type_in(&self, encoding: Encoding) -> String {
+        if encoding == Encoding::Utf8 {
+            return self.content_type().to_owned();
+        }
+        let charset = format!("charset={}", encoding.label());
+        let mut params = self
+            .content_type()
+            .split("; ")
+            .filter(|param| !param.starts_with("charset="))
+            .collect::<Vec<&str>>();
+        params.insert(1, &charset);
+        params.join("; ")
+    }
+
+    /// Chooses the most preferred format in the `Accept` header value.
+    ///
+    /// Returns `None` if no media range in it is supported.
+    pub fn from_accept(accept: &str) -> Option<Self> {
+        let mut preferred: Option<(f32, Self)> = None;
+        for media_range in accept.split(',') {
+            let mut params = media_range.split(';').map(str::trim);
+            let format = match params.next().unwrap_or_default() {
+                "*/*" | "application/*" | "application/json" => Self::Json,
+                "application/x-ndjson" | "application/ndjson" => Self::Ndjson,
+                "text/csv" => Self::Csv,
+                "text/tab-separated-values" => Self::Tsv,
+                "application/sql" => Self::Sql,
+                _ => continue,
+            };
+            let q = params
+                .find_map(|param| param.strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if q > 0.0 && !matches!(preferred, Some((max, _)) if max >= q) {
+                preferred = Some((q, format));
+            }
+        }
+        preferred.map(|(_, format)| format)
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OutputOptions {
+    /// Format of `date_of_birth` (`[year]-[month]-[day]` if `None`)
+    pub date_format: Option<DateFormat>,
+    /// Output only the fields (all fields if `None`)
+    pub fields: Option<Fields>,
+    pub format: OutputFormat,
+    /// Used only for `OutputFormat::Sql`
+    pub sql: SqlOptions,
+}
+
+/// Renders a single record. JSON is rendered as an object instead of an array.
+pub fn render_one<T: serde::Serialize>(
+    options: &OutputOptions,
+    record: &T,
+) -> anyhow::Result<String> {
+    match options.format {
+        OutputFormat::Json => Ok(serde_json::to_string(&to_object(record, options)?)?),
+        _ => render(options, std::slice::from_ref(record)),
+    }
+}
+
+pub fn render<T: serde::Serialize>(
+    options: &OutputOptions,
+    records: &[T],
+) -> anyhow::Result<String> {
+    let objects = records
+        .iter()
+        .map(|record| to_object(record, options))
+        .collect::<anyhow::Result<Vec<Map<String, Value>>>>()?;
+    match options.format {
+        OutputFormat::Json => Ok(serde_json::to_string(&objects)?),
+        OutputFormat::Ndjson => objects.iter().try_fold(String::new(), |mut acc, object| {
+            acc.push_str(&serde_json::to_string(object)?);
+            acc.push('\n');
+            Ok(acc)
+        }),
+        OutputFormat::Csv => render_delimited(&objects, b',', csv::Terminator::CRLF),
+        OutputFormat::Tsv => render_delimited(&objects, b'\t', csv::Terminator::Any(b'\n')),
+        OutputFormat::Sql => render_sql(&objects, &options.sql),
+    }
+}
+
+fn to_object<T: serde::Serialize>(
+    record: &T,
+    options: &OutputOptions,
+) -> anyhow::Result<Map<String, Value>> {
+    let mut object = match serde_json::to_value(record)? {
+        Value::Object(map) => map,
+        _ => anyhow::bail!("record is not an object"),
+    };
+    if let (Some(date_format), Some(Value::String(date_of_birth))) =
+        (&options.date_format, object.get_mut("date_of_birth"))
+    {
+        let date = Date::from(date_of_birth.parse::<DateOfBirth>()?);
+        *date_of_birth = date_format.format(date)?;
+    }
+    match &options.fields {
+        Some(fields) => Ok(fields.project(object)),
+        None => Ok(object),
+    }
+}
+
+fn to_field(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// The keys of all objects in the order they first appear. Records may skip `None` fields
+fn union_keys(objects: &[Map<String, Value>]) -> Vec<String> {
+    let mut keys = Vec::<String>::new();
+    for key in objects.iter().flat_map(|object| object.keys()) {
+        if !keys.contains(key) {
+            keys.push(key.clone());
+        }
+    }
+    keys
+}
+
+fn render_delimited(
+    objects: &[Map<String, Value>],
+    delimiter: u8,
+    terminator: csv::Terminator,
+) -> anyhow::Result<String> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .terminator(terminator)
+        .from_writer(vec![]);
+    if objects.is_empty() {
+        return Ok(String::new());
+    }
+    let header = union_keys(objects);
+    writer.write_record(&header)?;
+    for object in objects {
+        writer.write_record(
+            header
+                .iter()
+                .map(|key| object.get(key).map(to_field).unwrap_or_default()),
+        )?;
+    }
+    let bytes = writer.into_inner().context("failed to flush csv writer")?;
+    Ok(String::from_utf8(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Serialize)]
+    struct Record {
+        name: &'static str,
+        kana: &'static str,
+        age: u32,
+    }
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                name: "山田",
+                kana: "やまだ",
+                age: 20,
+            },
+            Record {
+                name: "太郎, \"次郎\"",
+                kana: "たろう",
+                age: 30,
+            },
+        ]
+    }
+
+    fn options(format: OutputFormat) -> OutputOptions {
+        OutputOptions {
+            format,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        assert_eq!("json".parse::<OutputFormat>()?, OutputFormat::Json);
+        assert_eq!("ndjson".parse::<OutputFormat>()?, OutputFormat::Ndjson);
+        assert_eq!("csv".parse::<OutputFormat>()?, OutputFormat::Csv);
+        assert_eq!("tsv".parse::<OutputFormat>()?, OutputFormat::Tsv);
+        assert_eq!("sql".parse::<OutputFormat>()?, OutputFormat::Sql);
+        assert!("xml".parse::<OutputFormat>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_accept() {
+        assert_eq!(OutputFormat::from_accept("*/*"), Some(OutputFormat::Json));
+        assert_eq!(
+            OutputFormat::from_accept("text/csv"),
+            Some(OutputFormat::Csv)
+        );
+        assert_eq!(
+            OutputFormat::from_accept("application/json;q=0.5, text/tab-separated-values"),
+            Some(OutputFormat::Tsv)
+        );
+        assert_eq!(
+            OutputFormat::from_accept("text/csv;q=0, application/x-ndjson;q=0.1"),
+            Some(OutputFormat::Ndjson)
+        );
+        assert_eq!(OutputFormat::from_accept("text/html"), None);
+    }
+
+    #[test]
+    fn test_content_type_in() {
+        assert_eq!(
+            OutputFormat::Json.content_type_in(Encoding::Utf8),
+            "application/json"
+        );
+        assert_eq!(
+            OutputFormat::Csv.content_type_in(Encoding::EucJp),
+            "text/csv; charset=EUC-JP; header=present"
+        );
+        assert!(!OutputFormat::Json.allows(Encoding::ShiftJis));
+        assert!(!OutputFormat::Ndjson.allows(Encoding::Cp932));
+        assert!(OutputFormat::Tsv.allows(Encoding::EucJp));
+    }
+
+    #[test]
+    fn test_render() -> anyhow::Result<()> {
+        assert_eq!(
+            render(&options(OutputFormat::Json), &records())?,
+            r#"[{"name":"山田","kana":"やまだ","age":20},{"name":"太郎, \"次郎\"","kana":"たろう","age":30}]"#
+        );
+        assert_eq!(
+            render(&options(OutputFormat::Ndjson), &records())?,
+            "{\"name\":\"山田\",\"kana\":\"やまだ\",\"age\":20}\n{\"name\":\"太郎, \\\"次郎\\\"\",\"kana\":\"たろう\",\"age\":30}\n"
+        );
+        assert_eq!(
+            render(&options(OutputFormat::Csv), &records())?,
+            "name,kana,age\r\n山田,やまだ,20\r\n\"太郎, \"\"次郎\"\"\",たろう,30\r\n"
+        );
+        assert_eq!(
+            render(&options(OutputFormat::Tsv), &records())?,
+            "name\tkana\tage\n山田\tやまだ\t20\n\"太郎, \"\"次郎\"\"\"\tたろう\t30\n"
+        );
+        assert_eq!(
+            render(&options(OutputFormat::Sql), &records())?,
+            "INSERT INTO \"pi\" (\"name\", \"kana\", \"age\") VALUES\n  ('山田', 'やまだ', 20),\n  ('太郎, \"次郎\"', 'たろう', 30);\n"
+        );
+        assert_eq!(
+            render(&options(OutputFormat::Csv), &Vec::<Record>::new())?,
+            ""
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_missing_fields() -> anyhow::Result<()> {
+        #[derive(serde::Serialize)]
+        struct Record {
+            name: &'static str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            email: Option<&'static str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            age: Option<u32>,
+        }
+
+        let records = [
+            Record {
+                name: "山田",
+                email: None,
+                age: Some(20),
+            },
+            Record {
+                name: "太郎",
+                email: Some("taro@example.com"),
+                age: None,
+            },
+        ];
+        assert_eq!(
+            render(&options(OutputFormat::Csv), &records)?,
+            "name,age,email\r\n山田,20,\r\n太郎,,taro@example.com\r\n"
+        );
+        assert_eq!(
+            render(&options(OutputFormat::Sql), &records)?,
+            [
+                r#"INSERT INTO "pi" ("name", "age", "email") VALUES"#,
+                r#"  ('山田', 20, NULL),"#,
+                r#"  ('太郎', NULL, 'taro@example.com');"#,
+                "",
+            ]
+            .join("\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_fields() -> anyhow::Result<()> {
+        let options = |format| OutputOptions {
+            fields: Some("age,name:full_name".parse().expect("valid fields")),
+            format,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_one(&options(OutputFormat::Json), &records()[0])?,
+            r#"{"age":20,"full_name":"山田"}"#
+        );
+        assert_eq!(
+            render(&options(OutputFormat::Csv), &records())?,
+            "age,full_name\r\n20,山田\r\n30,\"太郎, \"\"次郎\"\"\"\r\n"
+        );
+        // fields the records skip are null
+        let options = OutputOptions {
+            fields: Some("name,email".parse()?),
+            ..Default::default()
+        };
+        assert_eq!(
+            render_one(&options, &records()[0])?,
+            r#"{"name":"山田","email":null}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_date_format() -> anyhow::Result<()> {
+        #[derive(serde::Serialize)]
+        struct Record {
+            date_of_birth: DateOfBirth,
+        }
+
+        let options = OutputOptions {
+            date_format: Some("%Y年%-m月%-d日".parse()?),
+            ..Default::default()
+        };
+        let record = Record {
+            date_of_birth: "2001-02-03".parse()?,
+        };
+        assert_eq!(
+            render_one(&options, &record)?,
+            r#"{"date_of_birth":"2001年2月3日"}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_one() -> anyhow::Result<()> {
+        let record = &records()[0];
+        assert_eq!(
+            render_one(&options(OutputFormat::Json), record)?,
+            r#"{"name":"山田","kana":"やまだ","age":20}"#
+        );
+        assert_eq!(
+            render_one(&options(OutputFormat::Csv), record)?,
+            "name,kana,age\r\n山田,やまだ,20\r\n"
+        );
+        Ok(())
+    }
+}
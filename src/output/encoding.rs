@@ -0,0 +1,115 @@
+use std::str::FromStr;
+
+use crate::model::Charset;
+
+#[derive(Debug, thiserror::Error)]
+#[error("{0} cannot be encoded in {1}")]
+pub struct UnencodableError(pub char, pub &'static str);
+
+/// The character encoding of the output
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    ShiftJis,
+    Cp932,
+    EucJp,
+}
+
+impl Encoding {
+    /// The charset the output must be in
+    pub fn charset(&self) -> Option<Charset> {
+        match self {
+            Self::Utf8 => None,
+            Self::ShiftJis => Some(Charset::ShiftJis),
+            Self::Cp932 => Some(Charset::Cp932),
+            Self::EucJp => Some(Charset::EucJp),
+        }
+    }
+
+    /// The IANA charset name
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Utf8 => "utf-8",
+            Self::ShiftJis => "Shift_JIS",
+            Self::Cp932 => "Windows-31J",
+            Self::EucJp => "EUC-JP",
+        }
+    }
+
+    pub fn encode(&self, s: &str) -> Result<Vec<u8>, UnencodableError> {
+        let Some(charset) = self.charset() else {
+            return Ok(s.as_bytes().to_vec());
+        };
+        if let Some(c) = charset.find_unrepresentable(s) {
+            return Err(UnencodableError(c, self.label()));
+        }
+        // encoding_rs encodes Shift_JIS as CP932. The charset has rejected the extensions
+        let encoding = match self {
+            Self::EucJp => encoding_rs::EUC_JP,
+            _ => encoding_rs::SHIFT_JIS,
+        };
+        Ok(encoding.encode(s).0.into_owned())
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "utf-8" => Ok(Self::Utf8),
+            "sjis" => Ok(Self::ShiftJis),
+            "cp932" => Ok(Self::Cp932),
+            "euc-jp" => Ok(Self::EucJp),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not a valid encoding (utf-8|sjis|cp932|euc-jp)"
+            )),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Encoding {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<Self>().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        assert_eq!("utf-8".parse::<Encoding>()?, Encoding::Utf8);
+        assert_eq!("sjis".parse::<Encoding>()?, Encoding::ShiftJis);
+        assert_eq!(
+            "jisx0208".parse::<Encoding>().unwrap_err().to_string(),
+            "jisx0208 is not a valid encoding (utf-8|sjis|cp932|euc-jp)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode() -> anyhow::Result<()> {
+        assert_eq!(Encoding::Utf8.encode("髙橋")?, "髙橋".as_bytes());
+        assert_eq!(
+            Encoding::ShiftJis.encode("山田,ﾔﾏﾀﾞ")?,
+            b"\x8e\x52\x93\x63,\xd4\xcf\xc0\xde"
+        );
+        assert_eq!(Encoding::EucJp.encode("山田")?, b"\xbb\xb3\xc5\xc4");
+        assert_eq!(Encoding::Cp932.encode("髙")?, b"\xfb\xfc");
+        assert_eq!(
+            Encoding::ShiftJis.encode("髙橋").unwrap_err().to_string(),
+            "髙 cannot be encoded in Shift_JIS"
+        );
+        assert!(Encoding::Cp932.encode("𠮷").is_err());
+        assert!(Encoding::ShiftJis.encode("C:\\").is_err());
+        assert_eq!(Encoding::Cp932.encode("C:\\")?, b"C:\\");
+        Ok(())
+    }
+}
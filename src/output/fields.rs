@@ -0,0 +1,152 @@
+use std::str::FromStr;
+
+use serde_json::{Map, Value};
+
+#[derive(Debug, thiserror::Error)]
+#[error("unknown field: {0}")]
+pub struct UnknownFieldError(pub String);
+
+/// Fields to output in order, optionally renamed, e.g. `first_name:given_name,date_of_birth`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Fields(Vec<(String, String)>);
+
+impl FromStr for Fields {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s
+            .split(',')
+            .map(|field| {
+                let (field, key) = field.split_once(':').unwrap_or((field, field));
+                if field.is_empty() || key.is_empty() {
+                    Err(anyhow::anyhow!(
+                        "{s} is not a valid field list (field[:key],...)"
+                    ))
+                } else {
+                    Ok((field.to_owned(), key.to_owned()))
+                }
+            })
+            .collect::<anyhow::Result<Vec<(String, String)>>>()?;
+        for (i, (field, key)) in fields.iter().enumerate() {
+            anyhow::ensure!(
+                !fields[..i].iter().any(|(f, _)| f == field),
+                "duplicate field: {field}"
+            );
+            anyhow::ensure!(
+                !fields[..i].iter().any(|(_, k)| k == key),
+                "duplicate key: {key}"
+            );
+        }
+        Ok(Self(fields))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Fields {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Fields {
+    /// The selected fields before renaming
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|(field, _)| field.as_str())
+    }
+
+    /// Fails if a field is not one of `keys`
+    pub fn check(&self, keys: &[&str]) -> Result<(), UnknownFieldError> {
+        match self.names().find(|field| !keys.contains(field)) {
+            Some(field) => Err(UnknownFieldError(field.to_owned())),
+            None => Ok(()),
+        }
+    }
+
+    /// Fields missing from the object are null, as records skip `None` fields
+    pub(super) fn project(&self, mut object: Map<String, Value>) -> Map<String, Value> {
+        self.0
+            .iter()
+            .map(|(field, key)| (key.clone(), object.remove(field).unwrap_or(Value::Null)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        assert_eq!(
+            "first_name:given_name,sex".parse::<Fields>()?,
+            Fields(vec![
+                ("first_name".to_owned(), "given_name".to_owned()),
+                ("sex".to_owned(), "sex".to_owned()),
+            ])
+        );
+        assert!("".parse::<Fields>().is_err());
+        assert!("first_name,".parse::<Fields>().is_err());
+        assert!("first_name:".parse::<Fields>().is_err());
+        assert_eq!(
+            "first_name,sex,first_name:given_name"
+                .parse::<Fields>()
+                .unwrap_err()
+                .to_string(),
+            "duplicate field: first_name"
+        );
+        assert_eq!(
+            "first_name:name,last_name:name"
+                .parse::<Fields>()
+                .unwrap_err()
+                .to_string(),
+            "duplicate key: name"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_project() -> anyhow::Result<()> {
+        let object = match json!({"first_name": "太郎", "last_name": "山田", "sex": "male"}) {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        };
+        let fields = "sex,first_name:given_name".parse::<Fields>()?;
+        assert_eq!(
+            Value::Object(fields.project(object.clone())),
+            json!({"sex": "male", "given_name": "太郎"})
+        );
+        assert_eq!(
+            fields
+                .project(object.clone())
+                .keys()
+                .collect::<Vec<&String>>(),
+            ["sex", "given_name"]
+        );
+        let fields = "sex,email".parse::<Fields>()?;
+        assert_eq!(
+            Value::Object(fields.project(object)),
+            json!({"sex": "male", "email": null})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_check() -> anyhow::Result<()> {
+        let fields = "sex,first_name:given_name".parse::<Fields>()?;
+        assert!(fields.check(&["first_name", "sex"]).is_ok());
+        assert!(matches!(
+            fields.check(&["sex", "given_name"]),
+            Err(UnknownFieldError(field)) if field == "first_name"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_names() -> anyhow::Result<()> {
+        let fields = "email:mail,sex".parse::<Fields>()?;
+        assert_eq!(fields.names().collect::<Vec<&str>>(), ["email", "sex"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+use std::str::FromStr;
+
+use serde_json::{Map, Value};
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SqlDialect {
+    #[default]
+    Postgresql,
+    Mysql,
+    Sqlite,
+}
+
+impl FromStr for SqlDialect {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "postgresql" => Ok(Self::Postgresql),
+            "mysql" => Ok(Self::Mysql),
+            "sqlite" => Ok(Self::Sqlite),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not a valid SQL dialect (postgresql|mysql|sqlite)"
+            )),
+        }
+    }
+}
+
+impl SqlDialect {
+    fn quote_identifier(&self, identifier: &str) -> String {
+        match self {
+            Self::Postgresql | Self::Sqlite => format!("\"{}\"", identifier.replace('"', "\"\"")),
+            Self::Mysql => format!("`{}`", identifier.replace('`', "``")),
+        }
+    }
+
+    fn quote_string(&self, s: &str) -> String {
+        match self {
+            Self::Postgresql | Self::Sqlite => format!("'{}'", s.replace('\'', "''")),
+            // MySQL treats backslashes in string literals as escape characters
+            Self::Mysql => format!("'{}'", s.replace('\\', "\\\\").replace('\'', "''")),
+        }
+    }
+
+    fn literal(&self, value: &Value) -> String {
+        match value {
+            Value::Null => "NULL".to_owned(),
+            Value::Bool(b) => match (self, b) {
+                (Self::Sqlite, true) => "1".to_owned(),
+                (Self::Sqlite, false) => "0".to_owned(),
+                (_, true) => "TRUE".to_owned(),
+                (_, false) => "FALSE".to_owned(),
+            },
+            Value::Number(n) => n.to_string(),
+            Value::String(s) => self.quote_string(s),
+            value => self.quote_string(&value.to_string()),
+        }
+    }
+}
+
+/// Column names for fields, e.g. `first_name:given_name,last_name:family_name`
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ColumnNames(Vec<(String, String)>);
+
+impl FromStr for ColumnNames {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| match pair.split_once(':') {
+                Some((field, column)) if !field.is_empty() && !column.is_empty() => {
+                    Ok((field.to_owned(), column.to_owned()))
+                }
+                _ => Err(anyhow::anyhow!(
+                    "{pair} is not a valid column name mapping (field:column)"
+                )),
+            })
+            .collect::<anyhow::Result<Vec<(String, String)>>>()
+            .map(Self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ColumnNames {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl ColumnNames {
+    fn get<'a>(&'a self, field: &'a str) -> &'a str {
+        self.0
+            .iter()
+            .find(|(f, _)| f == field)
+            .map(|(_, column)| column.as_str())
+            .unwrap_or(field)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SqlOptions {
+    pub column_names: ColumnNames,
+    pub dialect: SqlDialect,
+    pub rows_per_statement: usize,
+    /// Table name. `schema.table` is quoted as two identifiers
+    pub table: String,
+}
+
+impl Default for SqlOptions {
+    fn default() -> Self {
+        Self {
+            column_names: ColumnNames::default(),
+            dialect: SqlDialect::default(),
+            rows_per_statement: 100,
+            table: "pi".to_owned(),
+        }
+    }
+}
+
+pub(super) fn render_sql(
+    objects: &[Map<String, Value>],
+    options: &SqlOptions,
+) -> anyhow::Result<String> {
+    anyhow::ensure!(
+        options.rows_per_statement > 0,
+        "rows_per_statement must be greater than 0"
+    );
+    let dialect = options.dialect;
+    if objects.is_empty() {
+        return Ok(String::new());
+    }
+    let fields = super::union_keys(objects);
+    let table = options
+        .table
+        .split('.')
+        .map(|identifier| dialect.quote_identifier(identifier))
+        .collect::<Vec<String>>()
+        .join(".");
+    let columns = fields
+        .iter()
+        .map(|field| dialect.quote_identifier(options.column_names.get(field)))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let mut sql = String::new();
+    for chunk in objects.chunks(options.rows_per_statement) {
+        sql.push_str(&format!("INSERT INTO {table} ({columns}) VALUES\n"));
+        let rows = chunk
+            .iter()
+            .map(|object| {
+                let values = fields
+                    .iter()
+                    .map(|field| dialect.literal(object.get(field).unwrap_or(&Value::Null)))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                format!("  ({values})")
+            })
+            .collect::<Vec<String>>()
+            .join(",\n");
+        sql.push_str(&rows);
+        sql.push_str(";\n");
+    }
+    Ok(sql)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn objects() -> Vec<Map<String, Value>> {
+        [
+            json!({"first_name": "太郎", "last_name": "O'Brien", "age": 20, "note": null}),
+            json!({"first_name": "花子", "last_name": "C:\\", "age": 30, "note": true}),
+        ]
+        .into_iter()
+        .map(|value| match value {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_column_names_from_str() -> anyhow::Result<()> {
+        let column_names = "first_name:given_name,last_name:family_name".parse::<ColumnNames>()?;
+        assert_eq!(column_names.get("first_name"), "given_name");
+        assert_eq!(column_names.get("last_name"), "family_name");
+        assert_eq!(column_names.get("sex"), "sex");
+        assert!("first_name".parse::<ColumnNames>().is_err());
+        assert!("first_name:".parse::<ColumnNames>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_postgresql() -> anyhow::Result<()> {
+        let options = SqlOptions {
+            column_names: "first_name:given_name".parse()?,
+            table: "public.users".to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(
+            render_sql(&objects(), &options)?,
+            [
+                r#"INSERT INTO "public"."users" ("given_name", "last_name", "age", "note") VALUES"#,
+                r#"  ('太郎', 'O''Brien', 20, NULL),"#,
+                r#"  ('花子', 'C:\', 30, TRUE);"#,
+                "",
+            ]
+            .join("\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_mysql() -> anyhow::Result<()> {
+        let options = SqlOptions {
+            dialect: SqlDialect::Mysql,
+            rows_per_statement: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_sql(&objects(), &options)?,
+            [
+                r#"INSERT INTO `pi` (`first_name`, `last_name`, `age`, `note`) VALUES"#,
+                r#"  ('太郎', 'O''Brien', 20, NULL);"#,
+                r#"INSERT INTO `pi` (`first_name`, `last_name`, `age`, `note`) VALUES"#,
+                r#"  ('花子', 'C:\\', 30, TRUE);"#,
+                "",
+            ]
+            .join("\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite() -> anyhow::Result<()> {
+        let options = SqlOptions {
+            dialect: SqlDialect::Sqlite,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_sql(&objects()[1..], &options)?,
+            [
+                r#"INSERT INTO "pi" ("first_name", "last_name", "age", "note") VALUES"#,
+                r#"  ('花子', 'C:\', 30, 1);"#,
+                "",
+            ]
+            .join("\n")
+        );
+        assert_eq!(render_sql(&[], &options)?, "");
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+use std::path::Path;
+
+use anyhow::Context;
+use serde_json::{Map, Value};
+use time::{Date, OffsetDateTime};
+
+use crate::{
+    model::{DateFormat, DateOfBirth, KanaForm, Sex, PI},
+    output::UnknownFieldError,
+};
+
+/// A record shape read from a JSON or TOML file.
+///
+/// ```toml
+/// [[fields]]
+/// key = "id"
+/// type = "sequence"
+///
+/// [[fields]]
+/// key = "kana"
+/// type = "full_name_kana"
+/// form = "katakana"
+/// ```
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Template {
+    fields: Vec<TemplateField>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+struct TemplateField {
+    key: String,
+    #[serde(flatten)]
+    generator: Generator,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Generator {
+    FirstName,
+    LastName,
+    FullName {
+        #[serde(default = "default_separator")]
+        separator: String,
+    },
+    FirstNameKana {
+        #[serde(default)]
+        form: KanaForm,
+    },
+    LastNameKana {
+        #[serde(default)]
+        form: KanaForm,
+    },
+    FullNameKana {
+        #[serde(default)]
+        form: KanaForm,
+        #[serde(default = "default_separator")]
+        separator: String,
+    },
+    /// `format` is a `DateFormat`, e.g. `[year]/[month]/[day]` or `%Y年%-m月%-d日`
+    DateOfBirth {
+        format: Option<DateFormat>,
+    },
+    /// Age as of `as_of` (default: today)
+    Age {
+        as_of: Option<DateOfBirth>,
+    },
+    DayOfWeek,
+    StarSign,
+    Zodiac,
+    Sex {
+        #[serde(default = "default_female")]
+        female: Value,
+        #[serde(default = "default_male")]
+        male: Value,
+    },
+    Constant {
+        value: Value,
+    },
+    /// `start`, `start + step`, `start + step * 2`, ...
+    Sequence {
+        #[serde(default = "default_one")]
+        start: i64,
+        #[serde(default = "default_one")]
+        step: i64,
+    },
+    /// Any field of the generated record, e.g. `email`
+    Field {
+        field: String,
+    },
+}
+
+fn default_separator() -> String {
+    " ".to_owned()
+}
+
+fn default_female() -> Value {
+    Value::from("female")
+}
+
+fn default_male() -> Value {
+    Value::from("male")
+}
+
+fn default_one() -> i64 {
+    1
+}
+
+impl Template {
+    /// Reads a template. The format is chosen by the extension (`.json` or `.toml`).
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let template = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => serde_json::from_str::<Self>(&s)?,
+            Some("toml") => toml::from_str::<Self>(&s)?,
+            _ => anyhow::bail!("template must be a .json or .toml file"),
+        };
+        Ok(template)
+    }
+
+    /// The keys of rendered records
+    pub fn keys(&self) -> Vec<&str> {
+        self.fields.iter().map(|field| field.key.as_str()).collect()
+    }
+
+    /// Renders the `index`-th record. Kana in `pi` must be hiragana.
+    pub fn render(&self, pi: &PI, index: usize) -> anyhow::Result<Map<String, Value>> {
+        let object = match serde_json::to_value(pi)? {
+            Value::Object(map) => map,
+            _ => anyhow::bail!("record is not an object"),
+        };
+        let mut record = Map::new();
+        for TemplateField { key, generator } in &self.fields {
+            let value = match generator {
+                Generator::FirstName => Value::from(pi.first_name.clone()),
+                Generator::LastName => Value::from(pi.last_name.clone()),
+                Generator::FullName { separator } => {
+                    Value::from(format!("{}{separator}{}", pi.last_name, pi.first_name))
+                }
+                Generator::FirstNameKana { form } => {
+                    Value::from(form.convert(&pi.first_name_kana)?)
+                }
+                Generator::LastNameKana { form } => Value::from(form.convert(&pi.last_name_kana)?),
+                Generator::FullNameKana { form, separator } => Value::from(format!(
+                    "{}{separator}{}",
+                    form.convert(&pi.last_name_kana)?,
+                    form.convert(&pi.first_name_kana)?
+                )),
+                Generator::DateOfBirth { format: None } => serde_json::to_value(pi.date_of_birth)?,
+                Generator::DateOfBirth {
+                    format: Some(format),
+                } => Value::from(format.format(Date::from(pi.date_of_birth))?),
+                Generator::Age { as_of } => Value::from(pi.date_of_birth.age(match as_of {
+                    Some(as_of) => Date::from(*as_of),
+                    None => OffsetDateTime::now_utc().date(),
+                })),
+                Generator::DayOfWeek => Value::from(pi.date_of_birth.day_of_week()),
+                Generator::StarSign => Value::from(pi.date_of_birth.star_sign()),
+                Generator::Zodiac => Value::from(pi.date_of_birth.zodiac()),
+                Generator::Sex { female, male } => match pi.sex {
+                    Sex::Female => female.clone(),
+                    Sex::Male => male.clone(),
+                },
+                Generator::Constant { value } => value.clone(),
+                Generator::Sequence { start, step } => {
+                    Value::from(start + step * i64::try_from(index)?)
+                }
+                Generator::Field { field } => object
+                    .get(field)
+                    .cloned()
+                    .ok_or_else(|| UnknownFieldError(field.clone()))?,
+            };
+            record.insert(key.clone(), value);
+        }
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::model::Name;
+
+    use super::*;
+
+    fn pi() -> PI {
+        let name = Name {
+            first_name: "太郎".to_owned(),
+            first_name_kana: "たろう".to_owned(),
+            last_name: "山田".to_owned(),
+            last_name_kana: "やまだ".to_owned(),
+        };
+        PI::from((name, Sex::Male, "1985-04-01".parse().expect("valid date")))
+    }
+
+    #[test]
+    fn test_json() -> anyhow::Result<()> {
+        let template = serde_json::from_value::<Template>(json!({
+            "fields": [
+                {"key": "id", "type": "sequence", "start": 100, "step": 10},
+                {"key": "name", "type": "full_name", "separator": "　"},
+                {"key": "kana", "type": "full_name_kana", "form": "halfwidth_kana"},
+                {"key": "birthday", "type": "date_of_birth", "format": "[year]/[month]/[day]"},
+                {"key": "gender", "type": "sex", "female": 2, "male": 1},
+                {"key": "age", "type": "age", "as_of": "2020-03-31"},
+                {"key": "zodiac", "type": "zodiac"},
+                {"key": "active", "type": "constant", "value": true},
+                {"key": "sex", "type": "field", "field": "sex"},
+            ]
+        }))?;
+        assert_eq!(
+            Value::Object(template.render(&pi(), 2)?),
+            json!({
+                "id": 120,
+                "name": "山田　太郎",
+                "kana": "ﾔﾏﾀﾞ ﾀﾛｳ",
+                "birthday": "1985/04/01",
+                "gender": 1,
+                "age": 34,
+                "zodiac": "丑",
+                "active": true,
+                "sex": "male",
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_toml() -> anyhow::Result<()> {
+        let template = toml::from_str::<Template>(
+            r#"
+            [[fields]]
+            key = "given_name"
+            type = "first_name"
+
+            [[fields]]
+            key = "given_name_kana"
+            type = "first_name_kana"
+            form = "katakana"
+
+            [[fields]]
+            key = "dob"
+            type = "date_of_birth"
+            "#,
+        )?;
+        let record = template.render(&pi(), 0)?;
+        assert_eq!(
+            record.keys().collect::<Vec<&String>>(),
+            ["given_name", "given_name_kana", "dob"]
+        );
+        assert_eq!(
+            Value::Object(record),
+            json!({"given_name": "太郎", "given_name_kana": "タロウ", "dob": "1985-04-01"})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid() -> anyhow::Result<()> {
+        assert!(serde_json::from_value::<Template>(json!({
+            "fields": [{"key": "id", "type": "uuid"}]
+        }))
+        .is_err());
+        assert!(serde_json::from_value::<Template>(json!({
+            "fields": [{"key": "dob", "type": "date_of_birth", "format": "[yaer]"}]
+        }))
+        .is_err());
+        let template = serde_json::from_value::<Template>(json!({
+            "fields": [{"key": "email", "type": "field", "field": "email"}]
+        }))?;
+        assert!(template.render(&pi(), 0).is_err());
+        Ok(())
+    }
+}
@@ -13,17 +13,20 @@ use tower_http::{
 use tracing::Level;
 
 use crate::{
-    config::Config, handler::generate_pi, infrastructure::NamesCache, model::HasNameGenerator,
+    config::Config,
+    handler::{generate_pi, wareki},
+    infrastructure::NameSource,
+    model::HasNameGenerator,
     use_case::HasGeneratePiUseCase,
 };
 
 #[derive(Clone, Debug)]
 pub struct AppState {
-    name_generator: NamesCache,
+    name_generator: NameSource,
 }
 
 impl HasNameGenerator for AppState {
-    type NameGenerator = NamesCache;
+    type NameGenerator = NameSource;
 
     fn name_generator(&self) -> &Self::NameGenerator {
         &self.name_generator
@@ -31,10 +34,10 @@ impl HasNameGenerator for AppState {
 }
 
 impl HasGeneratePiUseCase for AppState {
-    type GeneratePiUseCase = NamesCache;
+    type GeneratePiUseCase = Self;
 
     fn generate_pi_use_case(&self) -> &Self::GeneratePiUseCase {
-        &self.name_generator
+        self
     }
 }
 
@@ -42,9 +45,14 @@ pub async fn run_server() -> anyhow::Result<()> {
     let config = Config::from_env()?;
 
     let state = AppState {
-        name_generator: NamesCache::default(),
+        name_generator: NameSource::new(config.name_source_options())?,
     };
-    let router = Router::new().merge(generate_pi::route::<AppState>());
+    if let Some(interval) = config.names_refresh_interval {
+        state.name_generator.spawn_prefetch(interval);
+    }
+    let router = Router::new()
+        .merge(generate_pi::route::<AppState>())
+        .merge(wareki::route::<AppState>());
     let router = if config.base_path.is_empty() {
         router
     } else {
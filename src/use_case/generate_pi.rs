@@ -1,11 +1,720 @@
-use crate::model::{GenPiError, KanaForm, PI};
+use rand::{distributions::Distribution, rngs::StdRng, Rng, SeedableRng};
+use time::{Date, OffsetDateTime};
+
+use crate::model::{
+    Address, AgeDistribution, Charset, DateOfBirth, DateOfBirthConstraints, EmailDomains,
+    EmailGenerator, FemaleRatio, GenNameError, GenPiError, HasNameGenerator, KanaForm, NameFilter,
+    NameGenerator, Normalization, PhoneNumberFormat, PhoneNumberType, PopulationDateOfBirth,
+    Romanization, Sex, Wareki, WarekiFormat, PI,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct GeneratePiOptions {
+    /// Add an address to the output
+    pub address: bool,
+    /// Add the age to the output
+    pub age: bool,
+    pub age_distribution: AgeDistribution,
+    /// The date to compute ages against instead of today
+    pub as_of: Option<DateOfBirth>,
+    /// Use only names all characters of which are in the charset. Kana are checked in hiragana.
+    /// Fails with `KanaForm::HalfwidthKana` if the charset has no halfwidth kana
+    pub charset: Option<Charset>,
+    pub date_of_birth: DateOfBirthConstraints,
+    /// Add the day of the week of the date of birth to the output
+    pub day_of_week: bool,
+    /// Replace names with edge cases to stress-test validation and collation
+    pub edge_cases: bool,
+    /// Add an email address unique within the batch to the output
+    pub email: bool,
+    pub email_domains: EmailDomains,
+    /// The probability of female. Ignored if `sex` is specified
+    pub female_ratio: Option<FemaleRatio>,
+    pub kana_form: KanaForm,
+    /// Conditions all names must meet, including edge cases.
+    /// Fails with `GenNameError::NoMatch` if no edge case meets them
+    pub name_filter: NameFilter,
+    /// Normalize names after generating them. `charset` and `name_filter` check normalized names
+    pub normalization: Option<Normalization>,
+    /// Add a phone number of the type to the output
+    pub phone_number: Option<PhoneNumberType>,
+    pub phone_number_format: PhoneNumberFormat,
+    /// Add romanized names to the output
+    pub romanization: Option<Romanization>,
+    /// Seed for the random number generator.
+    /// The same seed and the same name source produce the same PI.
+    pub seed: Option<u64>,
+    /// Fix the sex of all records
+    pub sex: Option<Sex>,
+    /// Add the name source which generated each name to the output
+    pub source: bool,
+    /// Add the star sign to the output
+    pub star_sign: bool,
+    /// Add the date of birth in the Japanese calendar to the output
+    pub wareki: Option<WarekiFormat>,
+    /// Add the zodiac sign of the year of birth to the output
+    pub zodiac: bool,
+}
+
+impl GeneratePiOptions {
+    /// Turns on what generates the fields, e.g. `age` turns on `age`.
+    /// Generators with a kind use the default one. Unknown fields are ignored
+    pub fn enable_fields<'a>(&mut self, fields: impl IntoIterator<Item = &'a str>) {
+        for field in fields {
+            match field {
+                "age" => self.age = true,
+                "date_of_birth_wareki" => {
+                    self.wareki.get_or_insert_with(WarekiFormat::default);
+                }
+                "day_of_week" => self.day_of_week = true,
+                "email" => self.email = true,
+                "first_name_romaji" | "last_name_romaji" => {
+                    self.romanization.get_or_insert_with(Romanization::default);
+                }
+                "phone_number" => {
+                    self.phone_number
+                        .get_or_insert_with(PhoneNumberType::default);
+                }
+                "source" => self.source = true,
+                "star_sign" => self.star_sign = true,
+                "zodiac" => self.zodiac = true,
+                "postal_code" | "prefecture" | "prefecture_kana" | "city" | "city_kana"
+                | "town" | "town_kana" | "block_number" | "building" => self.address = true,
+                _ => {}
+            }
+        }
+    }
+}
 
 #[async_trait::async_trait]
 pub trait GeneratePiUseCase {
-    async fn generate_pi(&self, kana_form: KanaForm) -> Result<PI, GenPiError>;
+    async fn generate_pis(
+        &self,
+        count: usize,
+        options: GeneratePiOptions,
+    ) -> Result<Vec<PI>, GenPiError>;
+
+    async fn generate_pi(&self, options: GeneratePiOptions) -> Result<PI, GenPiError> {
+        let mut pis = self.generate_pis(1, options).await?;
+        Ok(pis.pop().expect("pis.len() == 1"))
+    }
 }
 
 pub trait HasGeneratePiUseCase {
     type GeneratePiUseCase: GeneratePiUseCase + Send + Sync;
     fn generate_pi_use_case(&self) -> &Self::GeneratePiUseCase;
 }
+
+#[async_trait::async_trait]
+impl<T: HasNameGenerator + Sync> GeneratePiUseCase for T {
+    // <https://github.com/tokio-rs/tracing/issues/2876>
+    #[tracing::instrument(skip(self), err)]
+    #[allow(clippy::blocks_in_conditions)]
+    async fn generate_pis(
+        &self,
+        count: usize,
+        options: GeneratePiOptions,
+    ) -> Result<Vec<PI>, GenPiError> {
+        let today = match options.as_of {
+            Some(as_of) => Date::from(as_of),
+            None => OffsetDateTime::now_utc().date(),
+        };
+        let date_of_birth_range = options
+            .date_of_birth
+            .range(today)
+            .map_err(GenPiError::DateOfBirthRange)?;
+        let mut rng = match options.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let sexes = (0..count)
+            .map(|_| match (options.sex, options.female_ratio) {
+                (Some(sex), _) => sex,
+                (None, Some(female_ratio)) => rng.sample(female_ratio),
+                (None, None) => rng.gen::<Sex>(),
+            })
+            .collect::<Vec<Sex>>();
+        if let (KanaForm::HalfwidthKana, Some(charset)) = (options.kana_form, options.charset) {
+            if !charset.contains('ｱ') {
+                return Err(GenPiError::Charset(format!(
+                    "halfwidth kana are not in {charset:?}"
+                )));
+            }
+        }
+        // names are filtered as they are output
+        let name_filter = match options.charset {
+            Some(charset) => options.name_filter.clone().with_charset(charset),
+            None => options.name_filter.clone(),
+        }
+        .with_normalization(options.normalization);
+        let (female_names, female_source) = self
+            .name_generator()
+            .generate_many_with_source(
+                Sex::Female,
+                sexes.iter().filter(|sex| **sex == Sex::Female).count(),
+                &name_filter,
+                &mut rng,
+            )
+            .await
+            .map_err(GenPiError::GenNameError)?;
+        let (male_names, male_source) = self
+            .name_generator()
+            .generate_many_with_source(
+                Sex::Male,
+                sexes.iter().filter(|sex| **sex == Sex::Male).count(),
+                &name_filter,
+                &mut rng,
+            )
+            .await
+            .map_err(GenPiError::GenNameError)?;
+        let mut female_names = female_names.into_iter();
+        let mut male_names = male_names.into_iter();
+
+        let population = match options.age_distribution {
+            AgeDistribution::Uniform => None,
+            AgeDistribution::Population => Some((
+                PopulationDateOfBirth::new(Sex::Female, date_of_birth_range.clone(), today),
+                PopulationDateOfBirth::new(Sex::Male, date_of_birth_range.clone(), today),
+            )),
+        };
+
+        let mut email_generator = options
+            .email
+            .then(|| EmailGenerator::new(options.email_domains.clone()));
+
+        let mut pis = Vec::with_capacity(count);
+        for sex in sexes {
+            let name = match sex {
+                Sex::Female => female_names.next(),
+                Sex::Male => male_names.next(),
+            }
+            .expect("generate_many returns count names");
+            let name = if options.edge_cases {
+                name.with_edge_cases(sex, &name_filter, &mut rng)
+                    .ok_or(GenPiError::GenNameError(GenNameError::NoMatch))?
+            } else {
+                name
+            };
+            let name = match options.normalization {
+                Some(normalization) => name.normalize(normalization),
+                None => name,
+            };
+            let date = match (&population, sex) {
+                (None, _) => rng.gen_range(date_of_birth_range.clone()),
+                (Some((female, _)), Sex::Female) => female.sample(&mut rng),
+                (Some((_, male)), Sex::Male) => male.sample(&mut rng),
+            };
+            // romanize before converting kana because it expects hiragana
+            let romaji = options
+                .romanization
+                .map(|romanization| {
+                    Ok::<_, anyhow::Error>((
+                        romanization.romanize(&name.first_name_kana)?,
+                        romanization.romanize(&name.last_name_kana)?,
+                    ))
+                })
+                .transpose()
+                .map_err(|e| GenPiError::Romanization(e.to_string()))?;
+            let email = email_generator
+                .as_mut()
+                .map(|email_generator| email_generator.generate(&name, date, &mut rng))
+                .transpose()
+                .map_err(|e| GenPiError::Email(e.to_string()))?;
+            let name = match options.kana_form {
+                KanaForm::Hiragana => name,
+                KanaForm::Katakana => name.in_katakana(),
+                KanaForm::HalfwidthKana => name.in_halfwidth_kana(),
+            };
+            let mut pi = PI::from((name, sex, date));
+            pi.email = email;
+            if options.source {
+                pi.source = match sex {
+                    Sex::Female => female_source,
+                    Sex::Male => male_source,
+                }
+                .map(ToOwned::to_owned);
+            }
+            pi.age = options.age.then(|| date.age(today));
+            pi.day_of_week = options.day_of_week.then(|| date.day_of_week().to_owned());
+            pi.star_sign = options.star_sign.then(|| date.star_sign().to_owned());
+            pi.zodiac = options.zodiac.then(|| date.zodiac().to_owned());
+            pi.date_of_birth_wareki = options
+                .wareki
+                .map(|format| {
+                    Wareki::try_from(Date::from(date))
+                        .map(|wareki| wareki.format(format))
+                        .map_err(|e| GenPiError::Wareki(e.to_string()))
+                })
+                .transpose()?;
+            if let Some((first_name_romaji, last_name_romaji)) = romaji {
+                pi.first_name_romaji = Some(first_name_romaji);
+                pi.last_name_romaji = Some(last_name_romaji);
+            }
+            pi.phone_number = options.phone_number.map(|phone_number_type| {
+                rng.sample(phone_number_type)
+                    .format(options.phone_number_format)
+            });
+            if options.address {
+                let address = rng.gen::<Address>();
+                pi.address = Some(match options.kana_form {
+                    KanaForm::Hiragana => address,
+                    KanaForm::Katakana => address.in_katakana(),
+                    KanaForm::HalfwidthKana => address.in_halfwidth_kana(),
+                });
+            }
+            pis.push(pi);
+        }
+        Ok(pis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{seq::SliceRandom, RngCore};
+
+    use crate::model::{DateOfBirthRangeError, Name};
+
+    use super::*;
+
+    /// Returns `name`, or a random one of a few names without kana if it is `None`
+    #[derive(Clone, Debug, Default)]
+    struct MockNameGenerator {
+        name: Option<Name>,
+    }
+
+    impl MockNameGenerator {
+        fn new(
+            last_name: &str,
+            last_name_kana: &str,
+            first_name: &str,
+            first_name_kana: &str,
+        ) -> Self {
+            Self {
+                name: Some(Name {
+                    first_name: first_name.to_string(),
+                    first_name_kana: first_name_kana.to_string(),
+                    last_name: last_name.to_string(),
+                    last_name_kana: last_name_kana.to_string(),
+                }),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl NameGenerator for MockNameGenerator {
+        async fn generate(
+            &self,
+            sex: Sex,
+            rng: &mut (dyn RngCore + Send),
+        ) -> Result<Name, GenNameError> {
+            if let Some(name) = &self.name {
+                return Ok(name.clone());
+            }
+            let first_names = match sex {
+                Sex::Female => ["花子", "由子", "美穂"],
+                Sex::Male => ["太郎", "敦", "治"],
+            };
+            Ok(Name {
+                first_name: first_names.choose(rng).expect("not empty").to_string(),
+                first_name_kana: "".to_string(),
+                last_name: ["山田", "菊地", "大貫"]
+                    .choose(rng)
+                    .expect("not empty")
+                    .to_string(),
+                last_name_kana: "".to_string(),
+            })
+        }
+    }
+
+    impl HasNameGenerator for MockNameGenerator {
+        type NameGenerator = Self;
+
+        fn name_generator(&self) -> &Self::NameGenerator {
+            self
+        }
+    }
+
+    #[tokio::test]
+    async fn test_seed() -> anyhow::Result<()> {
+        let generate = |seed| async move {
+            let options = GeneratePiOptions {
+                seed: Some(seed),
+                ..Default::default()
+            };
+            let pi = MockNameGenerator::default().generate_pi(options).await?;
+            anyhow::Ok(serde_json::to_string(&pi)?)
+        };
+        assert_eq!(generate(1).await?, generate(1).await?);
+        assert_ne!(generate(1).await?, generate(2).await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_date_of_birth_constraints() -> anyhow::Result<()> {
+        let options = GeneratePiOptions {
+            date_of_birth: DateOfBirthConstraints {
+                born_after: Some("1999-12-31".parse()?),
+                born_before: Some("2000-01-02".parse()?),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let pis = MockNameGenerator::default()
+            .generate_pis(10, options)
+            .await?;
+        for pi in pis {
+            assert_eq!(pi.date_of_birth, "2000-01-01".parse()?);
+        }
+
+        let options = GeneratePiOptions {
+            date_of_birth: DateOfBirthConstraints {
+                min_age: Some(2),
+                max_age: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            MockNameGenerator::default().generate_pis(1, options).await,
+            Err(GenPiError::DateOfBirthRange(
+                DateOfBirthRangeError::InvertedAge
+            ))
+        ));
+
+        let options = GeneratePiOptions {
+            date_of_birth: DateOfBirthConstraints {
+                born_after: Some("2000-01-01".parse()?),
+                born_before: Some("1990-01-01".parse()?),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            MockNameGenerator::default().generate_pis(1, options).await,
+            Err(GenPiError::DateOfBirthRange(DateOfBirthRangeError::Empty))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_enable_fields() -> anyhow::Result<()> {
+        let mut options = GeneratePiOptions {
+            phone_number: Some(PhoneNumberType::Mobile),
+            ..Default::default()
+        };
+        options.enable_fields([
+            "age",
+            "date_of_birth_wareki",
+            "day_of_week",
+            "email",
+            "first_name_romaji",
+            "phone_number",
+            "postal_code",
+            "source",
+            "star_sign",
+            "zodiac",
+            "nickname",
+        ]);
+        // a kind given explicitly is kept
+        assert_eq!(options.phone_number, Some(PhoneNumberType::Mobile));
+        let pi = MockNameGenerator::new("山田", "やまだ", "太郎", "たろう")
+            .generate_pi(options)
+            .await?;
+        assert!(pi.age.is_some());
+        assert!(pi.date_of_birth_wareki.is_some());
+        assert!(pi.day_of_week.is_some());
+        assert!(pi.email.is_some());
+        assert_eq!(pi.first_name_romaji.as_deref(), Some("Tarō"));
+        assert!(pi
+            .phone_number
+            .is_some_and(|n| n.starts_with("070") || n.starts_with("080") || n.starts_with("090")));
+        assert!(pi.address.is_some());
+        assert!(pi.star_sign.is_some());
+        assert!(pi.zodiac.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_edge_cases() -> anyhow::Result<()> {
+        let options = GeneratePiOptions {
+            edge_cases: true,
+            romanization: Some(Romanization::Hepburn),
+            ..Default::default()
+        };
+        let pis = MockNameGenerator::default()
+            .generate_pis(10, options)
+            .await?;
+        for pi in pis {
+            assert!(
+                !["山田", "菊地", "大貫"].contains(&pi.last_name.as_str())
+                    || !["花子", "由子", "美穂", "太郎", "敦", "治"]
+                        .contains(&pi.first_name.as_str()),
+                "{pi:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_name_filter() -> anyhow::Result<()> {
+        let options = GeneratePiOptions {
+            edge_cases: true,
+            name_filter: "last_name:contains:山,first_name:length:1".parse()?,
+            sex: Some(Sex::Male),
+            ..Default::default()
+        };
+        let pis = MockNameGenerator::default()
+            .generate_pis(10, options)
+            .await?;
+        for pi in pis {
+            assert!(pi.last_name.contains('山'), "{pi:?}");
+            assert_eq!(pi.first_name.chars().count(), 1, "{pi:?}");
+        }
+
+        let options = GeneratePiOptions {
+            name_filter: "last_name:contains:中".parse()?,
+            ..Default::default()
+        };
+        assert!(matches!(
+            MockNameGenerator::default().generate_pis(1, options).await,
+            Err(GenPiError::GenNameError(GenNameError::NoMatch))
+        ));
+
+        // 大貫 太郎 matches but no edge case does
+        let options = GeneratePiOptions {
+            edge_cases: true,
+            name_filter: "last_name:starts_with:大,first_name:starts_with:太".parse()?,
+            sex: Some(Sex::Male),
+            ..Default::default()
+        };
+        assert!(matches!(
+            MockNameGenerator::default().generate_pis(1, options).await,
+            Err(GenPiError::GenNameError(GenNameError::NoMatch))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_charset() -> anyhow::Result<()> {
+        let options = GeneratePiOptions {
+            charset: Some(Charset::ShiftJis),
+            edge_cases: true,
+            normalization: Some(Normalization::Nfc),
+            ..Default::default()
+        };
+        let pis = MockNameGenerator::default()
+            .generate_pis(20, options)
+            .await?;
+        for pi in pis {
+            assert_eq!(
+                Charset::ShiftJis.find_unrepresentable(&pi.last_name),
+                None,
+                "{pi:?}"
+            );
+            assert_eq!(
+                Charset::ShiftJis.find_unrepresentable(&pi.first_name),
+                None,
+                "{pi:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_charset_after_normalization() -> anyhow::Result<()> {
+        // が as か and the combining voiced sound mark
+        let generator = MockNameGenerator::new("長谷川", "はせか\u{3099}わ", "花子", "はなこ");
+        let options = GeneratePiOptions {
+            charset: Some(Charset::JisX0208),
+            normalization: Some(Normalization::Nfc),
+            ..Default::default()
+        };
+        let pi = generator.generate_pi(options).await?;
+        assert_eq!(pi.last_name_kana, "はせがわ");
+
+        let options = GeneratePiOptions {
+            charset: Some(Charset::JisX0208),
+            ..Default::default()
+        };
+        assert!(matches!(
+            generator.generate_pi(options).await,
+            Err(GenPiError::GenNameError(GenNameError::NoMatch))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_charset_halfwidth_kana() -> anyhow::Result<()> {
+        let options = GeneratePiOptions {
+            charset: Some(Charset::JisX0208),
+            kana_form: KanaForm::HalfwidthKana,
+            ..Default::default()
+        };
+        assert!(matches!(
+            MockNameGenerator::default().generate_pis(1, options).await,
+            Err(GenPiError::Charset(_))
+        ));
+        let options = GeneratePiOptions {
+            charset: Some(Charset::ShiftJis),
+            kana_form: KanaForm::HalfwidthKana,
+            ..Default::default()
+        };
+        MockNameGenerator::default()
+            .generate_pis(1, options)
+            .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_derived_fields() -> anyhow::Result<()> {
+        let options = GeneratePiOptions {
+            age: true,
+            as_of: Some("2020-01-01".parse()?),
+            date_of_birth: DateOfBirthConstraints {
+                min_age: Some(20),
+                max_age: Some(20),
+                ..Default::default()
+            },
+            day_of_week: true,
+            zodiac: true,
+            ..Default::default()
+        };
+        let pis = MockNameGenerator::default()
+            .generate_pis(10, options)
+            .await?;
+        for pi in pis {
+            assert_eq!(pi.age, Some(20));
+            assert!(pi.day_of_week.is_some());
+            assert!(pi.star_sign.is_none());
+            assert!(matches!(pi.zodiac.as_deref(), Some("卯" | "辰")));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sex() -> anyhow::Result<()> {
+        let options = GeneratePiOptions {
+            sex: Some(Sex::Female),
+            female_ratio: Some("0".parse()?),
+            ..Default::default()
+        };
+        let pis = MockNameGenerator::default()
+            .generate_pis(10, options)
+            .await?;
+        assert!(pis.iter().all(|pi| pi.sex == Sex::Female));
+
+        let options = GeneratePiOptions {
+            female_ratio: Some("0".parse()?),
+            ..Default::default()
+        };
+        let pis = MockNameGenerator::default()
+            .generate_pis(10, options)
+            .await?;
+        assert!(pis.iter().all(|pi| pi.sex == Sex::Male));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_romanization() -> anyhow::Result<()> {
+        let generator = MockNameGenerator::new("大貫", "おおぬき", "純一", "じゅんいち");
+        let options = GeneratePiOptions {
+            kana_form: KanaForm::Katakana,
+            romanization: Some(Romanization::Passport),
+            ..Default::default()
+        };
+        let pi = generator.generate_pi(options).await?;
+        assert_eq!(pi.first_name_kana, "ジュンイチ");
+        assert_eq!(pi.first_name_romaji.as_deref(), Some("Junichi"));
+        assert_eq!(pi.last_name_romaji.as_deref(), Some("Onuki"));
+
+        let pi = generator.generate_pi(GeneratePiOptions::default()).await?;
+        assert!(!serde_json::to_string(&pi)?.contains("romaji"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_email() -> anyhow::Result<()> {
+        let options = GeneratePiOptions {
+            email: true,
+            email_domains: "example.org".parse()?,
+            kana_form: KanaForm::Katakana,
+            ..Default::default()
+        };
+        let pis = MockNameGenerator::new("佐藤", "さとう", "花子", "はなこ")
+            .generate_pis(20, options)
+            .await?;
+        let emails = pis
+            .iter()
+            .filter_map(|pi| pi.email.clone())
+            .collect::<std::collections::HashSet<String>>();
+        assert_eq!(emails.len(), 20);
+        assert!(emails
+            .iter()
+            .all(|email| email.contains("sato") && email.ends_with("@example.org")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_email_error() -> anyhow::Result<()> {
+        let options = GeneratePiOptions {
+            email: true,
+            ..Default::default()
+        };
+        // kana must be in hiragana
+        assert!(matches!(
+            MockNameGenerator::new("山田", "ヤマダ", "太郎", "タロウ")
+                .generate_pi(options)
+                .await,
+            Err(GenPiError::Email(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_address() -> anyhow::Result<()> {
+        let options = GeneratePiOptions {
+            address: true,
+            kana_form: KanaForm::Katakana,
+            ..Default::default()
+        };
+        let pi = MockNameGenerator::default().generate_pi(options).await?;
+        let address = pi.address.as_ref().expect("address is generated");
+        assert!(!address.prefecture_kana.is_empty());
+        assert!(address
+            .prefecture_kana
+            .chars()
+            .all(|c| ('\u{30a1}'..='\u{30f6}').contains(&c)));
+        let json = serde_json::to_value(&pi)?;
+        assert!(json.get("postal_code").is_some());
+        assert!(json.get("address").is_none());
+
+        let pi = MockNameGenerator::default()
+            .generate_pi(GeneratePiOptions::default())
+            .await?;
+        assert!(serde_json::to_value(&pi)?.get("postal_code").is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_pis() -> anyhow::Result<()> {
+        let options = GeneratePiOptions {
+            seed: Some(1),
+            ..Default::default()
+        };
+        let pis = MockNameGenerator::default()
+            .generate_pis(3, options.clone())
+            .await?;
+        assert_eq!(pis.len(), 3);
+        let pi = MockNameGenerator::default().generate_pi(options).await?;
+        assert_eq!(
+            serde_json::to_string(&pis[0].sex)?,
+            serde_json::to_string(&pi.sex)?
+        );
+
+        let pis = MockNameGenerator::default()
+            .generate_pis(0, GeneratePiOptions::default())
+            .await?;
+        assert!(pis.is_empty());
+        Ok(())
+    }
+}